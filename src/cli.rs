@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "dbdiff")]
@@ -22,17 +22,54 @@ pub enum Commands {
         /// Optional name for the snapshot
         #[arg(short, long)]
         name: Option<String>,
+
+        /// Also write the schema to schema.lock.json for committing to version control
+        #[arg(long)]
+        write_lock: bool,
     },
 
     /// Compare two snapshots or current schema with latest snapshot
     Diff {
-        /// First snapshot ID or "latest"
+        /// Snapshot ID, name, "latest", "latest~N", "lock" or "current" (default: latest)
         #[arg(short, long)]
         from: Option<String>,
 
-        /// Second snapshot ID or "current"
+        /// Snapshot ID, name, "latest", "latest~N", "lock" or "current" (default: current)
         #[arg(short, long)]
         to: Option<String>,
+
+        /// Output format for the diff
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Print the PostgreSQL migration that applies the diff instead of the diff itself
+        #[arg(long, conflicts_with = "format")]
+        sql: bool,
+
+        /// Print the PostgreSQL migration that reverts the diff (to -> from)
+        #[arg(long, conflicts_with_all = ["format", "sql"])]
+        rollback: bool,
+    },
+
+    /// Check for schema drift against a baseline, exiting non-zero on drift
+    ///
+    /// Exit codes: 0 = no drift, 1 = drift, 2 = error
+    Check {
+        /// Baseline reference: snapshot ID, name, "latest", "latest~N" or "lock" (default: latest)
+        #[arg(short, long)]
+        baseline: Option<String>,
+
+        /// Reference to check against the baseline (default: current)
+        #[arg(short, long)]
+        against: Option<String>,
+
+        /// Which changes count as drift
+        #[arg(long, value_enum, default_value_t = FailOn::Any)]
+        fail_on: FailOn,
+
+        /// Output format for the detected drift
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 
     /// Show snapshot history
@@ -42,3 +79,19 @@ pub enum Commands {
         limit: u32,
     },
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable output
+    Text,
+    /// Versioned JSON document for tools and CI
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    /// Fail on any schema change
+    Any,
+    /// Fail only on changes that can lose data or break dependents (drops, narrowing type changes)
+    Destructive,
+}
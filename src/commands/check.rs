@@ -0,0 +1,200 @@
+use anyhow::Result;
+use colored::*;
+
+use crate::cli::{FailOn, OutputFormat};
+use crate::commands::diff::resolve_schema;
+use crate::config::Config;
+use crate::diff::engine::SchemaDiff;
+use crate::output::{json, terminal};
+use crate::storage::sqlite::Storage;
+
+/// Exit code when the schema matches the baseline
+pub const EXIT_NO_DRIFT: i32 = 0;
+/// Exit code when drift matching `--fail-on` was found
+pub const EXIT_DRIFT: i32 = 1;
+/// Exit code when the check itself could not run
+pub const EXIT_ERROR: i32 = 2;
+
+/// Runs the drift check and returns the process exit code
+pub async fn execute(
+    baseline: Option<String>,
+    against: Option<String>,
+    fail_on: FailOn,
+    format: OutputFormat,
+) -> Result<i32> {
+    let against_ref = against.unwrap_or_else(|| "current".to_string());
+
+    let config = Config::load()?;
+    let storage = Storage::open().await?;
+
+    let (baseline_ref, old) = match baseline {
+        Some(reference) => {
+            let schema = resolve_schema(&config, &storage, &reference).await?;
+            (reference, schema)
+        }
+        None => match storage.get_latest_snapshot().await? {
+            Some(schema) => ("latest".to_string(), schema),
+            None => anyhow::bail!("No baseline snapshot found. Run 'dbdiff snapshot' first."),
+        },
+    };
+    let new = resolve_schema(&config, &storage, &against_ref).await?;
+
+    let mut diff = SchemaDiff::compare(&old, &new);
+    diff.ignore_partitions(&config.ignore_partitions);
+    diff.ignore_roles(&config.ignore_roles);
+    let code = exit_code(&diff, fail_on);
+    let drift = code == EXIT_DRIFT;
+
+    match format {
+        OutputFormat::Json => json::print_diff(&diff, &baseline_ref, &against_ref)?,
+        OutputFormat::Text => {
+            println!("Checking {} against baseline {}\n", against_ref, baseline_ref);
+            terminal::print_diff(&diff);
+
+            if drift {
+                println!("\n{} Schema drift detected", "✗".red().bold());
+            } else if diff.has_changes() {
+                println!(
+                    "\n{} No destructive drift (non-destructive changes ignored)",
+                    "✓".green().bold()
+                );
+            } else {
+                println!("\n{} No drift", "✓".green().bold());
+            }
+        }
+    }
+
+    Ok(code)
+}
+
+/// Exit code for a diff: drift if it has changes that `fail_on` counts
+fn exit_code(diff: &SchemaDiff, fail_on: FailOn) -> i32 {
+    let drift = match fail_on {
+        FailOn::Any => diff.has_changes(),
+        FailOn::Destructive => diff.has_destructive_changes(),
+    };
+
+    if drift {
+        EXIT_DRIFT
+    } else {
+        EXIT_NO_DRIFT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::models::{Column, EnumType, Routine, RoutineKind, Schema, Table, Volatility};
+
+    fn column(name: &str, data_type: &str, position: i32) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: position,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants: Vec::new(),
+        }
+    }
+
+    fn orders(columns: Vec<Column>) -> Schema {
+        Schema::with_tables(vec![Table {
+            name: "orders".to_string(),
+            schema: "public".to_string(),
+            columns,
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            partition_of: None,
+            rls_enabled: false,
+            rls_forced: false,
+            policies: Vec::new(),
+            owner: None,
+            grants: Vec::new(),
+        }])
+    }
+
+    fn exit_codes(old: &Schema, new: &Schema) -> (i32, i32) {
+        let diff = SchemaDiff::compare(old, new);
+        (exit_code(&diff, FailOn::Any), exit_code(&diff, FailOn::Destructive))
+    }
+
+    #[test]
+    fn no_changes_pass_both_modes() {
+        let schema = orders(vec![column("id", "integer", 1)]);
+
+        assert_eq!(exit_codes(&schema, &schema), (EXIT_NO_DRIFT, EXIT_NO_DRIFT));
+    }
+
+    #[test]
+    fn additive_changes_fail_only_on_any() {
+        let old = orders(vec![column("id", "integer", 1)]);
+        let new = orders(vec![column("id", "integer", 1), column("notes", "text", 2)]);
+
+        assert_eq!(exit_codes(&old, &new), (EXIT_DRIFT, EXIT_NO_DRIFT));
+    }
+
+    #[test]
+    fn widening_passes_destructive() {
+        let old = orders(vec![column("code", "character varying(50)", 1)]);
+        let new = orders(vec![column("code", "character varying(255)", 1)]);
+
+        assert_eq!(exit_codes(&old, &new), (EXIT_DRIFT, EXIT_NO_DRIFT));
+    }
+
+    #[test]
+    fn dropped_columns_fail_both_modes() {
+        let old = orders(vec![column("id", "integer", 1), column("notes", "text", 2)]);
+        let new = orders(vec![column("id", "integer", 1)]);
+
+        assert_eq!(exit_codes(&old, &new), (EXIT_DRIFT, EXIT_DRIFT));
+    }
+
+    #[test]
+    fn narrowing_fails_both_modes() {
+        let old = orders(vec![column("code", "character varying(255)", 1)]);
+        let new = orders(vec![column("code", "character varying(50)", 1)]);
+
+        assert_eq!(exit_codes(&old, &new), (EXIT_DRIFT, EXIT_DRIFT));
+    }
+
+    #[test]
+    fn dropped_routines_and_types_fail_both_modes() {
+        let mut old = Schema::new();
+        old.routines.push(Routine {
+            name: "total".to_string(),
+            schema: "public".to_string(),
+            kind: RoutineKind::Function,
+            argument_types: String::new(),
+            arguments: String::new(),
+            return_type: Some("integer".to_string()),
+            language: "sql".to_string(),
+            volatility: Volatility::Volatile,
+            security_definer: false,
+            body: "SELECT 1".to_string(),
+            strict: false,
+            leakproof: false,
+            parallel: None,
+            cost: None,
+            rows: None,
+            config: Vec::new(),
+            definition: None,
+            owner: None,
+            grants: Vec::new(),
+        });
+        assert_eq!(exit_codes(&old, &Schema::new()), (EXIT_DRIFT, EXIT_DRIFT));
+
+        let mut old = Schema::new();
+        old.enums.push(EnumType {
+            name: "mood".to_string(),
+            schema: "public".to_string(),
+            labels: vec!["happy".to_string()],
+        });
+        assert_eq!(exit_codes(&old, &Schema::new()), (EXIT_DRIFT, EXIT_DRIFT));
+    }
+}
@@ -1,14 +1,205 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-pub async fn execute(from: Option<String>, to: Option<String>) -> Result<()> {
+use crate::cli::OutputFormat;
+use crate::config::Config;
+use crate::diff::engine::SchemaDiff;
+use crate::output::{json, sql as sql_output, terminal};
+use crate::schema::models::Schema;
+use crate::schema::postgres;
+use crate::storage::lock;
+use crate::storage::sqlite::Storage;
+
+pub async fn execute(
+    from: Option<String>,
+    to: Option<String>,
+    format: OutputFormat,
+    sql: bool,
+    rollback: bool,
+) -> Result<()> {
     let from_ref = from.unwrap_or_else(|| "latest".to_string());
     let to_ref = to.unwrap_or_else(|| "current".to_string());
 
-    println!("Comparing {} -> {}", from_ref, to_ref);
+    let config = Config::load()?;
+    let storage = Storage::open().await?;
+
+    let old = resolve_schema(&config, &storage, &from_ref).await?;
+    let new = resolve_schema(&config, &storage, &to_ref).await?;
+
+    let mut diff = SchemaDiff::compare(&old, &new);
+    diff.ignore_partitions(&config.ignore_partitions);
+    diff.ignore_roles(&config.ignore_roles);
 
-    // TODO: Load snapshots
-    // TODO: Run diff engine
-    // TODO: Display results
+    if rollback {
+        println!("-- Rollback from {} to {}", to_ref, from_ref);
+        sql_output::print_rollback(&diff)?;
+    } else if sql {
+        println!("-- Migration from {} to {}", from_ref, to_ref);
+        sql_output::print_migration(&diff)?;
+    } else {
+        match format {
+            OutputFormat::Text => {
+                println!("Comparing {} -> {}\n", from_ref, to_ref);
+                terminal::print_diff(&diff);
+            }
+            OutputFormat::Json => json::print_diff(&diff, &from_ref, &to_ref)?,
+        }
+    }
 
     Ok(())
 }
+
+/// Resolve a schema reference to a schema.
+///
+/// Accepts `current` (live extraction), `lock` (the committed lock file),
+/// `latest`, `latest~N`, a snapshot ID, or a snapshot name. IDs take
+/// precedence over names that look numeric.
+pub async fn resolve_schema(config: &Config, storage: &Storage, reference: &str) -> Result<Schema> {
+    if reference == "current" {
+        return postgres::extract_schema(config)
+            .await
+            .context("Failed to extract current schema");
+    }
+
+    if reference == "lock" {
+        return lock::read_lock(&Config::lock_path());
+    }
+
+    let id = resolve_snapshot_id(storage, reference).await?;
+    storage.load_snapshot(id).await
+}
+
+/// Resolve a snapshot reference (anything but `current` or `lock`) to a snapshot ID
+pub async fn resolve_snapshot_id(storage: &Storage, reference: &str) -> Result<i64> {
+    if let Some(offset) = parse_latest_offset(reference)? {
+        return match storage.get_snapshot_id_from_latest(offset).await? {
+            Some(id) => Ok(id),
+            None if offset == 0 => {
+                anyhow::bail!("No snapshots found. Run 'dbdiff snapshot' first.")
+            }
+            None => anyhow::bail!(
+                "No snapshot at '{}': history has fewer than {} snapshots",
+                reference,
+                offset + 1
+            ),
+        };
+    }
+
+    if let Ok(id) = reference.parse::<i64>() {
+        if storage.snapshot_exists(id).await? {
+            return Ok(id);
+        }
+    }
+
+    match storage.find_snapshot_by_name(reference).await? {
+        Some(id) => Ok(id),
+        None => anyhow::bail!(
+            "Unknown reference '{}'. Use a snapshot ID, a snapshot name, 'latest', 'latest~N', 'lock' or 'current'.",
+            reference
+        ),
+    }
+}
+
+/// Parse `latest` / `latest~N` into an offset from the latest snapshot
+fn parse_latest_offset(reference: &str) -> Result<Option<u32>> {
+    if reference == "latest" {
+        return Ok(Some(0));
+    }
+
+    match reference.strip_prefix("latest~") {
+        Some(n) => {
+            let offset = n
+                .parse::<u32>()
+                .with_context(|| format!("Invalid reference '{}': expected latest~N", reference))?;
+            Ok(Some(offset))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Store with snapshots named `a`, `3` and `b`, which get IDs 1, 2 and 3
+    async fn storage() -> Storage {
+        let storage = Storage::in_memory().await.unwrap();
+        for name in ["a", "3", "b"] {
+            storage.save_snapshot(name, &Schema::new()).await.unwrap();
+        }
+        storage
+    }
+
+    #[test]
+    fn parses_latest_offsets() {
+        assert_eq!(parse_latest_offset("latest").unwrap(), Some(0));
+        assert_eq!(parse_latest_offset("latest~2").unwrap(), Some(2));
+        assert_eq!(parse_latest_offset("release-1").unwrap(), None);
+        assert!(parse_latest_offset("latest~x").is_err());
+        assert!(parse_latest_offset("latest~-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_latest() {
+        let storage = storage().await;
+
+        assert_eq!(resolve_snapshot_id(&storage, "latest").await.unwrap(), 3);
+        assert_eq!(resolve_snapshot_id(&storage, "latest~2").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_latest_past_the_end() {
+        let storage = storage().await;
+        let error = resolve_snapshot_id(&storage, "latest~3").await.unwrap_err();
+
+        assert!(error.to_string().contains("fewer than 4 snapshots"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn rejects_latest_without_snapshots() {
+        let storage = Storage::in_memory().await.unwrap();
+        let error = resolve_snapshot_id(&storage, "latest").await.unwrap_err();
+
+        assert!(error.to_string().contains("No snapshots found"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_numeric_offset() {
+        let storage = storage().await;
+
+        assert!(resolve_snapshot_id(&storage, "latest~x").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn prefers_ids_over_numeric_names() {
+        let storage = storage().await;
+
+        // ID 3 is `b`, not the snapshot named `3`
+        assert_eq!(resolve_snapshot_id(&storage, "3").await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_numeric_names() {
+        let storage = Storage::in_memory().await.unwrap();
+        storage.save_snapshot("a", &Schema::new()).await.unwrap();
+        storage.save_snapshot("7", &Schema::new()).await.unwrap();
+
+        // No snapshot has ID 7, so the name matches
+        assert_eq!(resolve_snapshot_id(&storage, "7").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn resolves_names() {
+        let storage = storage().await;
+
+        assert_eq!(resolve_snapshot_id(&storage, "a").await.unwrap(), 1);
+        assert_eq!(resolve_snapshot_id(&storage, "b").await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_names() {
+        let storage = storage().await;
+        let error = resolve_snapshot_id(&storage, "missing").await.unwrap_err();
+
+        assert!(error.to_string().contains("Unknown reference 'missing'"), "{}", error);
+    }
+}
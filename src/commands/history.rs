@@ -1,10 +1,58 @@
 use anyhow::Result;
+use colored::*;
+
+use crate::config::Config;
+use crate::diff::engine::SchemaDiff;
+use crate::output::terminal;
+use crate::storage::sqlite::Storage;
 
 pub async fn execute(limit: u32) -> Result<()> {
-    println!("Showing last {} snapshots", limit);
+    let config = Config::load()?;
+    let storage = Storage::open().await?;
+
+    // Fetch one extra snapshot so the oldest shown entry has a predecessor
+    let snapshots = storage.list_snapshots(limit.saturating_add(1)).await?;
+
+    if snapshots.is_empty() {
+        println!("{}", "No snapshots yet. Run 'dbdiff snapshot' to create one.".yellow());
+        return Ok(());
+    }
+
+    let shown = snapshots.len().min(limit as usize);
+
+    // Entries run newest first, so each predecessor becomes the next entry's schema
+    let mut current = match snapshots.first() {
+        Some(info) => Some(storage.load_snapshot(info.id).await?),
+        None => None,
+    };
+
+    for (i, info) in snapshots.iter().take(shown).enumerate() {
+        println!(
+            "{} {}  {}  ({} tables)",
+            format!("#{}", info.id).yellow().bold(),
+            info.name.bold(),
+            info.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            info.table_count
+        );
+
+        let previous = match snapshots.get(i + 1) {
+            Some(previous) => Some(storage.load_snapshot(previous.id).await?),
+            None => None,
+        };
+
+        let summary = match (&previous, &current) {
+            (Some(old), Some(new)) => {
+                let mut diff = SchemaDiff::compare(old, new);
+                diff.ignore_partitions(&config.ignore_partitions);
+                diff.ignore_roles(&config.ignore_roles);
+                terminal::format_stats(&diff.stats())
+            }
+            _ => "initial snapshot".dimmed().to_string(),
+        };
+        current = previous;
 
-    // TODO: Query SQLite for snapshots
-    // TODO: Display formatted list
+        println!("    {}", summary);
+    }
 
     Ok(())
 }
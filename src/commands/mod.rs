@@ -2,3 +2,4 @@ pub mod init;
 pub mod snapshot;
 pub mod diff;
 pub mod history;
+pub mod check;
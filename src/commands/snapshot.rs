@@ -3,8 +3,10 @@ use colored::*;
 
 use crate::config::Config;
 use crate::schema::postgres;
+use crate::storage::lock;
+use crate::storage::sqlite::Storage;
 
-pub async fn execute(name: Option<String>) -> Result<()> {
+pub async fn execute(name: Option<String>, write_lock: bool) -> Result<()> {
     let config = Config::load()?;
 
     let snapshot_name = name.unwrap_or_else(|| {
@@ -12,7 +14,7 @@ pub async fn execute(name: Option<String>) -> Result<()> {
     });
 
     println!("Connecting to database...");
-    let schema = postgres::extract_schema(&config.connection_string).await?;
+    let schema = postgres::extract_schema(&config).await?;
 
     println!(
         "{} Captured {} tables",
@@ -31,12 +33,21 @@ pub async fn execute(name: Option<String>) -> Result<()> {
         );
     }
 
-    // TODO: Save to SQLite storage
+    let storage = Storage::open().await?;
+    let id = storage.save_snapshot(&snapshot_name, &schema).await?;
+
     println!(
-        "\n{} Snapshot '{}' created",
+        "\n{} Snapshot '{}' created (id {})",
         "✓".green().bold(),
-        snapshot_name
+        snapshot_name,
+        id
     );
 
+    if write_lock {
+        let path = Config::lock_path();
+        lock::write_lock(&path, &schema)?;
+        println!("{} Wrote {}", "✓".green().bold(), path.display());
+    }
+
     Ok(())
 }
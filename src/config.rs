@@ -4,15 +4,51 @@ use std::path::PathBuf;
 
 const CONFIG_DIR: &str = ".dbdiff";
 const CONFIG_FILE: &str = "config.json";
+const LOCK_FILE: &str = "schema.lock.json";
+
+const DEFAULT_STATEMENT_TIMEOUT_MS: u64 = 60_000;
+const DEFAULT_LOCK_TIMEOUT_MS: u64 = 2_000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub connection_string: String,
+
+    /// Maximum time for any single catalog query during extraction (0 = no limit)
+    #[serde(default = "default_statement_timeout_ms")]
+    pub statement_timeout_ms: u64,
+
+    /// Maximum time to wait for a lock during extraction (0 = no limit)
+    #[serde(default = "default_lock_timeout_ms")]
+    pub lock_timeout_ms: u64,
+
+    /// Glob patterns (`*`, `?`) of partition names whose creation is left out
+    /// of diffs, e.g. `events_20*` for monthly partitions added by a job
+    #[serde(default)]
+    pub ignore_partitions: Vec<String>,
+
+    /// Glob patterns (`*`, `?`) of roles whose grants and ownership are left
+    /// out of diffs, e.g. `ci_*` for temporary users created by test runs
+    #[serde(default)]
+    pub ignore_roles: Vec<String>,
+}
+
+fn default_statement_timeout_ms() -> u64 {
+    DEFAULT_STATEMENT_TIMEOUT_MS
+}
+
+fn default_lock_timeout_ms() -> u64 {
+    DEFAULT_LOCK_TIMEOUT_MS
 }
 
 impl Config {
     pub fn new(connection_string: String) -> Self {
-        Self { connection_string }
+        Self {
+            connection_string,
+            statement_timeout_ms: DEFAULT_STATEMENT_TIMEOUT_MS,
+            lock_timeout_ms: DEFAULT_LOCK_TIMEOUT_MS,
+            ignore_partitions: Vec::new(),
+            ignore_roles: Vec::new(),
+        }
     }
 
     /// Get the .dbdiff directory path
@@ -30,6 +66,11 @@ impl Config {
         Self::dir_path().join("snapshots.db")
     }
 
+    /// Get the schema lock file path (in the current directory, meant to be committed)
+    pub fn lock_path() -> PathBuf {
+        PathBuf::from(LOCK_FILE)
+    }
+
     /// Check if dbdiff is initialized in current directory
     pub fn is_initialized() -> bool {
         Self::file_path().exists()
@@ -1,47 +1,370 @@
-use crate::schema::models::{Schema, Table, Column};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use super::legacy;
+use crate::schema::models::{
+    Schema, Table, Column, Index, Constraint, Trigger, Sequence, EnumType, Domain, CompositeType,
+    View, MaterializedView, Routine, EventTrigger, Partitioning, PartitionBound, Policy, Grant,
+    SchemaPrivileges, ConstraintType,
+};
 
 /// Result of comparing two schemas
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SchemaDiff {
+    pub schemas_added: Vec<String>,
+    pub schemas_removed: Vec<String>,
     pub tables_added: Vec<Table>,
     pub tables_removed: Vec<Table>,
     pub tables_modified: Vec<TableDiff>,
+    /// Partitions that were created or dropped, kept apart from tables so
+    /// they can be shown under their parent. Changes to existing partitions
+    /// are in `tables_modified`.
+    pub partitions_added: Vec<Table>,
+    pub partitions_removed: Vec<Table>,
+    pub sequences_added: Vec<Sequence>,
+    pub sequences_removed: Vec<Sequence>,
+    pub sequences_modified: Vec<SequenceDiff>,
+    pub enums_added: Vec<EnumType>,
+    pub enums_removed: Vec<EnumType>,
+    pub enums_modified: Vec<EnumDiff>,
+    pub domains_added: Vec<Domain>,
+    pub domains_removed: Vec<Domain>,
+    pub domains_modified: Vec<DomainDiff>,
+    pub composite_types_added: Vec<CompositeType>,
+    pub composite_types_removed: Vec<CompositeType>,
+    pub composite_types_modified: Vec<CompositeTypeDiff>,
+    pub views_added: Vec<View>,
+    pub views_removed: Vec<View>,
+    pub views_modified: Vec<ViewDiff>,
+    pub materialized_views_added: Vec<MaterializedView>,
+    pub materialized_views_removed: Vec<MaterializedView>,
+    pub materialized_views_modified: Vec<ViewDiff>,
+    pub routines_added: Vec<Routine>,
+    pub routines_removed: Vec<Routine>,
+    pub routines_modified: Vec<RoutineDiff>,
+    pub event_triggers_added: Vec<EventTrigger>,
+    pub event_triggers_removed: Vec<EventTrigger>,
+    pub event_triggers_modified: Vec<EventTriggerDiff>,
+    /// Owner and grants of added and removed schemas
+    pub schema_privileges_added: Vec<SchemaPrivileges>,
+    pub schema_privileges_removed: Vec<SchemaPrivileges>,
+    pub schema_privileges_modified: Vec<SchemaPrivilegesDiff>,
 }
 
 /// Changes within a single table
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TableDiff {
+    pub schema_name: String,
     pub table_name: String,
     pub columns_added: Vec<Column>,
     pub columns_removed: Vec<Column>,
     pub columns_modified: Vec<ColumnDiff>,
+    pub indexes_added: Vec<Index>,
+    pub indexes_removed: Vec<Index>,
+    pub indexes_modified: Vec<IndexDiff>,
+    pub constraints_added: Vec<Constraint>,
+    pub constraints_removed: Vec<Constraint>,
+    pub constraints_modified: Vec<ConstraintDiff>,
+    pub triggers_added: Vec<Trigger>,
+    pub triggers_removed: Vec<Trigger>,
+    pub triggers_modified: Vec<TriggerDiff>,
+    /// Partitioning before and after; differs only when the table was repartitioned
+    pub old_partitioning: Option<Partitioning>,
+    pub new_partitioning: Option<Partitioning>,
+    /// Parent and bound before and after, for partitions that were attached,
+    /// detached or given a new bound
+    pub old_partition_of: Option<PartitionBound>,
+    pub new_partition_of: Option<PartitionBound>,
+    /// Row-level security switches before and after
+    pub old_rls_enabled: bool,
+    pub new_rls_enabled: bool,
+    pub old_rls_forced: bool,
+    pub new_rls_forced: bool,
+    pub policies_added: Vec<Policy>,
+    pub policies_removed: Vec<Policy>,
+    pub policies_modified: Vec<PolicyDiff>,
+    pub old_owner: Option<String>,
+    pub new_owner: Option<String>,
+    pub grants_added: Vec<Grant>,
+    pub grants_removed: Vec<Grant>,
+}
+
+/// Changes within a single view or materialized view
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewDiff {
+    pub schema_name: String,
+    pub view_name: String,
+    pub old_definition: String,
+    pub new_definition: String,
+    pub columns_added: Vec<Column>,
+    pub columns_removed: Vec<Column>,
+    pub columns_modified: Vec<ColumnDiff>,
+    /// Index changes and full index lists, always empty for plain views.
+    /// The full lists are needed to rebuild a materialized view.
+    pub indexes_added: Vec<Index>,
+    pub indexes_removed: Vec<Index>,
+    pub indexes_modified: Vec<IndexDiff>,
+    pub old_indexes: Vec<Index>,
+    pub new_indexes: Vec<Index>,
+    pub old_owner: Option<String>,
+    pub new_owner: Option<String>,
+    /// Full grant and column lists. A rebuilt view starts out without
+    /// grants, so all of them are needed to grant it again.
+    pub old_grants: Vec<Grant>,
+    pub new_grants: Vec<Grant>,
+    pub old_columns: Vec<Column>,
+    pub new_columns: Vec<Column>,
+}
+
+/// Changes to a single sequence's settings
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceDiff {
+    /// Schema-qualified name
+    pub sequence_name: String,
+    pub old: Sequence,
+    pub new: Sequence,
+}
+
+/// Changes to the labels of a single enum type
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumDiff {
+    /// Schema-qualified name
+    pub type_name: String,
+    pub old: EnumType,
+    pub new: EnumType,
+    pub labels_added: Vec<String>,
+    pub labels_removed: Vec<String>,
+    /// Whether labels present on both sides appear in a different order
+    pub labels_reordered: bool,
+}
+
+/// Changes to a single domain
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainDiff {
+    /// Schema-qualified name
+    pub type_name: String,
+    pub old: Domain,
+    pub new: Domain,
+}
+
+/// Changes to a single composite type
+#[derive(Debug, Clone, Serialize)]
+pub struct CompositeTypeDiff {
+    /// Schema-qualified name
+    pub type_name: String,
+    pub old: CompositeType,
+    pub new: CompositeType,
+}
+
+/// Changes to a single function or procedure, matched by full signature
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutineDiff {
+    pub signature: String,
+    pub old: Routine,
+    pub new: Routine,
+}
+
+/// Changes to a single table trigger
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerDiff {
+    pub trigger_name: String,
+    pub old: Trigger,
+    pub new: Trigger,
+}
+
+/// Changes to a single row-level security policy
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyDiff {
+    pub policy_name: String,
+    pub old: Policy,
+    pub new: Policy,
+}
+
+/// Changes to the owner or grants of a schema
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaPrivilegesDiff {
+    pub schema_name: String,
+    pub old: SchemaPrivileges,
+    pub new: SchemaPrivileges,
+}
+
+/// Changes to a single event trigger
+#[derive(Debug, Clone, Serialize)]
+pub struct EventTriggerDiff {
+    pub trigger_name: String,
+    pub old: EventTrigger,
+    pub new: EventTrigger,
 }
 
 /// Changes to a single column
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ColumnDiff {
     pub column_name: String,
     pub old: Column,
     pub new: Column,
+    /// How `data_type` changed, if it did
+    pub type_change: Option<TypeChange>,
+}
+
+/// Kind of change to a column's type
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum TypeChange {
+    /// Same type with a different length, e.g. `character varying(50)` -> `character varying(255)`
+    Length,
+    /// Same type with a different precision or scale, e.g. `numeric(10,2)` -> `numeric(12,4)`
+    Precision,
+    /// A different type altogether
+    Type,
+}
+
+/// Changes to a single index
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexDiff {
+    pub index_name: String,
+    pub old: Index,
+    pub new: Index,
+}
+
+/// Changes to a single constraint
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintDiff {
+    pub constraint_name: String,
+    pub old: Constraint,
+    pub new: Constraint,
+}
+
+/// Counts of changes in a diff, for compact summaries
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct DiffStats {
+    pub schemas_added: usize,
+    pub schemas_removed: usize,
+    pub tables_added: usize,
+    pub tables_removed: usize,
+    pub tables_modified: usize,
+    pub partitions_added: usize,
+    pub partitions_removed: usize,
+    pub sequences_added: usize,
+    pub sequences_removed: usize,
+    pub sequences_modified: usize,
+    pub types_added: usize,
+    pub types_removed: usize,
+    pub types_modified: usize,
+    pub columns_added: usize,
+    pub columns_removed: usize,
+    pub columns_modified: usize,
+    pub indexes_added: usize,
+    pub indexes_removed: usize,
+    pub indexes_modified: usize,
+    pub constraints_added: usize,
+    pub constraints_removed: usize,
+    pub constraints_modified: usize,
+    pub views_added: usize,
+    pub views_removed: usize,
+    pub views_modified: usize,
+    pub materialized_views_added: usize,
+    pub materialized_views_removed: usize,
+    pub materialized_views_modified: usize,
+    pub routines_added: usize,
+    pub routines_removed: usize,
+    pub routines_modified: usize,
+    pub triggers_added: usize,
+    pub triggers_removed: usize,
+    pub triggers_modified: usize,
+    pub policies_added: usize,
+    pub policies_removed: usize,
+    pub policies_modified: usize,
+    pub event_triggers_added: usize,
+    pub event_triggers_removed: usize,
+    pub event_triggers_modified: usize,
+    pub owners_changed: usize,
+    pub grants_added: usize,
+    pub grants_removed: usize,
 }
 
 impl SchemaDiff {
     pub fn compare(old: &Schema, new: &Schema) -> Self {
+        // Whatever the older snapshot's format did not record is unknown
+        // rather than empty, so it is taken from the other side
+        let (filled_old, filled_new);
+        let (old, new) = match old.format.cmp(&new.format) {
+            Ordering::Less => {
+                filled_old = legacy::fill_unknown(old, new);
+                (&filled_old, new)
+            }
+            Ordering::Greater => {
+                filled_new = legacy::fill_unknown(new, old);
+                (old, &filled_new)
+            }
+            Ordering::Equal => (old, new),
+        };
+
         let mut diff = SchemaDiff {
+            schemas_added: Vec::new(),
+            schemas_removed: Vec::new(),
             tables_added: Vec::new(),
             tables_removed: Vec::new(),
             tables_modified: Vec::new(),
+            partitions_added: Vec::new(),
+            partitions_removed: Vec::new(),
+            sequences_added: Vec::new(),
+            sequences_removed: Vec::new(),
+            sequences_modified: Vec::new(),
+            enums_added: Vec::new(),
+            enums_removed: Vec::new(),
+            enums_modified: Vec::new(),
+            domains_added: Vec::new(),
+            domains_removed: Vec::new(),
+            domains_modified: Vec::new(),
+            composite_types_added: Vec::new(),
+            composite_types_removed: Vec::new(),
+            composite_types_modified: Vec::new(),
+            views_added: Vec::new(),
+            views_removed: Vec::new(),
+            views_modified: Vec::new(),
+            materialized_views_added: Vec::new(),
+            materialized_views_removed: Vec::new(),
+            materialized_views_modified: Vec::new(),
+            routines_added: Vec::new(),
+            routines_removed: Vec::new(),
+            routines_modified: Vec::new(),
+            event_triggers_added: Vec::new(),
+            event_triggers_removed: Vec::new(),
+            event_triggers_modified: Vec::new(),
+            schema_privileges_added: Vec::new(),
+            schema_privileges_removed: Vec::new(),
+            schema_privileges_modified: Vec::new(),
         };
 
-        // Find added and modified tables
+        // Find added and removed schemas
+        let old_schemas = old.schema_names();
+        let new_schemas = new.schema_names();
+        for name in &new_schemas {
+            if !old_schemas.contains(name) {
+                diff.schemas_added.push(name.clone());
+            }
+        }
+        for name in &old_schemas {
+            if !new_schemas.contains(name) {
+                diff.schemas_removed.push(name.clone());
+            }
+        }
+
+        // Find added and modified tables, matched by (schema, name)
+        let old_tables = index_by(&old.tables, |t| (t.schema.as_str(), t.name.as_str()));
+        let new_tables = index_by(&new.tables, |t| (t.schema.as_str(), t.name.as_str()));
         for new_table in &new.tables {
-            match old.tables.iter().find(|t| t.name == new_table.name) {
+            match old_tables.get(&(new_table.schema.as_str(), new_table.name.as_str())) {
                 Some(old_table) => {
                     let table_diff = TableDiff::compare(old_table, new_table);
                     if table_diff.has_changes() {
                         diff.tables_modified.push(table_diff);
                     }
                 }
+                None if new_table.is_partition() => {
+                    diff.partitions_added.push(new_table.clone());
+                }
                 None => {
                     diff.tables_added.push(new_table.clone());
                 }
@@ -50,61 +373,1418 @@ impl SchemaDiff {
 
         // Find removed tables
         for old_table in &old.tables {
-            if !new.tables.iter().any(|t| t.name == old_table.name) {
-                diff.tables_removed.push(old_table.clone());
+            if !new_tables.contains_key(&(old_table.schema.as_str(), old_table.name.as_str())) {
+                if old_table.is_partition() {
+                    diff.partitions_removed.push(old_table.clone());
+                } else {
+                    diff.tables_removed.push(old_table.clone());
+                }
+            }
+        }
+
+        // Find added, modified and removed views
+        let (views_added, views_removed, views_modified) =
+            diff_by_key(&old.views, &new.views, View::qualified_name);
+        diff.views_added = views_added;
+        diff.views_removed = views_removed;
+        diff.views_modified = views_modified
+            .iter()
+            .map(|(old, new)| ViewDiff::compare(old, new))
+            .filter(ViewDiff::has_changes)
+            .collect();
+
+        // Find added, modified and removed materialized views
+        let (materialized_views_added, materialized_views_removed, materialized_views_modified) =
+            diff_by_key(&old.materialized_views, &new.materialized_views, MaterializedView::qualified_name);
+        diff.materialized_views_added = materialized_views_added;
+        diff.materialized_views_removed = materialized_views_removed;
+        diff.materialized_views_modified = materialized_views_modified
+            .iter()
+            .map(|(old, new)| ViewDiff::compare_materialized(old, new))
+            .filter(ViewDiff::has_changes)
+            .collect();
+
+        // Find added, modified and removed sequences
+        let (sequences_added, sequences_removed, sequences_modified) =
+            diff_by_key(&old.sequences, &new.sequences, Sequence::qualified_name);
+        diff.sequences_added = sequences_added;
+        diff.sequences_removed = sequences_removed;
+        diff.sequences_modified = sequences_modified
+            .into_iter()
+            .map(|(old, new)| SequenceDiff {
+                sequence_name: new.qualified_name(),
+                old,
+                new,
+            })
+            .collect();
+
+        // Find added, modified and removed user-defined types
+        let (enums_added, enums_removed, enums_modified) =
+            diff_by_key(&old.enums, &new.enums, EnumType::qualified_name);
+        diff.enums_added = enums_added;
+        diff.enums_removed = enums_removed;
+        diff.enums_modified = enums_modified
+            .into_iter()
+            .map(|(old, new)| EnumDiff::compare(old, new))
+            .collect();
+
+        let (domains_added, domains_removed, domains_modified) =
+            diff_by_key(&old.domains, &new.domains, Domain::qualified_name);
+        diff.domains_added = domains_added;
+        diff.domains_removed = domains_removed;
+        diff.domains_modified = domains_modified
+            .into_iter()
+            .map(|(old, new)| DomainDiff {
+                type_name: new.qualified_name(),
+                old,
+                new,
+            })
+            .collect();
+
+        let (composite_types_added, composite_types_removed, composite_types_modified) =
+            diff_by_key(&old.composite_types, &new.composite_types, CompositeType::qualified_name);
+        diff.composite_types_added = composite_types_added;
+        diff.composite_types_removed = composite_types_removed;
+        diff.composite_types_modified = composite_types_modified
+            .into_iter()
+            .map(|(old, new)| CompositeTypeDiff {
+                type_name: new.qualified_name(),
+                old,
+                new,
+            })
+            .collect();
+
+        // Find added, modified and removed routines, keyed by signature so
+        // overloads are told apart
+        let (routines_added, routines_removed, routines_modified) =
+            diff_by_key(&old.routines, &new.routines, Routine::signature);
+        diff.routines_added = routines_added;
+        diff.routines_removed = routines_removed;
+        diff.routines_modified = routines_modified
+            .into_iter()
+            .map(|(old, new)| RoutineDiff {
+                signature: new.signature(),
+                old,
+                new,
+            })
+            .collect();
+
+        // Find added, modified and removed event triggers
+        let (event_triggers_added, event_triggers_removed, event_triggers_modified) =
+            diff_by_key(&old.event_triggers, &new.event_triggers, |t| t.name.clone());
+        diff.event_triggers_added = event_triggers_added;
+        diff.event_triggers_removed = event_triggers_removed;
+        diff.event_triggers_modified = event_triggers_modified
+            .into_iter()
+            .map(|(old, new)| EventTriggerDiff {
+                trigger_name: new.name.clone(),
+                old,
+                new,
+            })
+            .collect();
+
+        // Schema privileges are only reported as added or removed along with
+        // their schema; a schema on both sides without them on one comes from
+        // a snapshot taken before privileges were recorded
+        for new_privileges in &new.schema_privileges {
+            match old.schema_privileges.iter().find(|p| p.name == new_privileges.name) {
+                Some(old_privileges) if old_privileges != new_privileges => {
+                    diff.schema_privileges_modified.push(SchemaPrivilegesDiff {
+                        schema_name: new_privileges.name.clone(),
+                        old: old_privileges.clone(),
+                        new: new_privileges.clone(),
+                    });
+                }
+                None if diff.schemas_added.contains(&new_privileges.name) => {
+                    diff.schema_privileges_added.push(new_privileges.clone());
+                }
+                _ => {}
+            }
+        }
+        for old_privileges in &old.schema_privileges {
+            if diff.schemas_removed.contains(&old_privileges.name) {
+                diff.schema_privileges_removed.push(old_privileges.clone());
             }
         }
 
         diff
     }
 
+    /// The diff that takes the new schema back to the old one.
+    ///
+    /// Removed tables and columns keep their full definitions, so the
+    /// inverse can recreate them.
+    pub fn invert(&self) -> SchemaDiff {
+        SchemaDiff {
+            schemas_added: self.schemas_removed.clone(),
+            schemas_removed: self.schemas_added.clone(),
+            tables_added: self.tables_removed.clone(),
+            tables_removed: self.tables_added.clone(),
+            tables_modified: self.tables_modified.iter().map(TableDiff::invert).collect(),
+            partitions_added: self.partitions_removed.clone(),
+            partitions_removed: self.partitions_added.clone(),
+            sequences_added: self.sequences_removed.clone(),
+            sequences_removed: self.sequences_added.clone(),
+            sequences_modified: self.sequences_modified.iter().map(SequenceDiff::invert).collect(),
+            enums_added: self.enums_removed.clone(),
+            enums_removed: self.enums_added.clone(),
+            enums_modified: self.enums_modified.iter().map(EnumDiff::invert).collect(),
+            domains_added: self.domains_removed.clone(),
+            domains_removed: self.domains_added.clone(),
+            domains_modified: self.domains_modified.iter().map(DomainDiff::invert).collect(),
+            composite_types_added: self.composite_types_removed.clone(),
+            composite_types_removed: self.composite_types_added.clone(),
+            composite_types_modified: self
+                .composite_types_modified
+                .iter()
+                .map(CompositeTypeDiff::invert)
+                .collect(),
+            views_added: self.views_removed.clone(),
+            views_removed: self.views_added.clone(),
+            views_modified: self.views_modified.iter().map(ViewDiff::invert).collect(),
+            materialized_views_added: self.materialized_views_removed.clone(),
+            materialized_views_removed: self.materialized_views_added.clone(),
+            materialized_views_modified: self
+                .materialized_views_modified
+                .iter()
+                .map(ViewDiff::invert)
+                .collect(),
+            routines_added: self.routines_removed.clone(),
+            routines_removed: self.routines_added.clone(),
+            routines_modified: self.routines_modified.iter().map(RoutineDiff::invert).collect(),
+            event_triggers_added: self.event_triggers_removed.clone(),
+            event_triggers_removed: self.event_triggers_added.clone(),
+            event_triggers_modified: self
+                .event_triggers_modified
+                .iter()
+                .map(EventTriggerDiff::invert)
+                .collect(),
+            schema_privileges_added: self.schema_privileges_removed.clone(),
+            schema_privileges_removed: self.schema_privileges_added.clone(),
+            schema_privileges_modified: self
+                .schema_privileges_modified
+                .iter()
+                .map(SchemaPrivilegesDiff::invert)
+                .collect(),
+        }
+    }
+
+    /// Drops added partitions whose name matches any of the glob `patterns`
+    /// (`*` and `?` wildcards), so routinely created partitions such as
+    /// `events_2024_03` do not show up in every diff
+    pub fn ignore_partitions(&mut self, patterns: &[String]) {
+        self.partitions_added
+            .retain(|p| !patterns.iter().any(|pattern| glob_matches(pattern, &p.name)));
+    }
+
+    /// Leaves out grants to, and ownership by, roles whose name matches any
+    /// of the glob `patterns`, such as short-lived CI users. Changes that
+    /// only concerned those roles disappear from the diff.
+    pub fn ignore_roles(&mut self, patterns: &[String]) {
+        if patterns.is_empty() {
+            return;
+        }
+        let ignored = |role: &str| patterns.iter().any(|pattern| glob_matches(pattern, role));
+        let scrub_grants = |grants: &mut Vec<Grant>| grants.retain(|g| !ignored(&g.grantee));
+        let scrub_owner = |owner: &mut Option<String>| {
+            if owner.as_deref().is_some_and(ignored) {
+                *owner = None;
+            }
+        };
+        // A handover to or from an ignored role is dropped on both sides
+        let scrub_owners = |old: &mut Option<String>, new: &mut Option<String>| {
+            if old.as_deref().is_some_and(ignored) || new.as_deref().is_some_and(ignored) {
+                *old = None;
+                *new = None;
+            }
+        };
+        let scrub_table = |table: &mut Table| {
+            scrub_owner(&mut table.owner);
+            scrub_grants(&mut table.grants);
+            for column in &mut table.columns {
+                scrub_grants(&mut column.grants);
+            }
+        };
+
+        let tables = self.tables_added.iter_mut().chain(&mut self.tables_removed);
+        for table in tables.chain(&mut self.partitions_added).chain(&mut self.partitions_removed) {
+            scrub_table(table);
+        }
+        for table_diff in &mut self.tables_modified {
+            scrub_owners(&mut table_diff.old_owner, &mut table_diff.new_owner);
+            scrub_grants(&mut table_diff.grants_added);
+            scrub_grants(&mut table_diff.grants_removed);
+            let columns = table_diff.columns_added.iter_mut().chain(&mut table_diff.columns_removed);
+            for column in columns {
+                scrub_grants(&mut column.grants);
+            }
+            for column_diff in &mut table_diff.columns_modified {
+                scrub_grants(&mut column_diff.old.grants);
+                scrub_grants(&mut column_diff.new.grants);
+            }
+            table_diff.columns_modified.retain(|c| c.old != c.new);
+        }
+        self.tables_modified.retain(TableDiff::has_changes);
+
+        for view in self.views_added.iter_mut().chain(&mut self.views_removed) {
+            scrub_owner(&mut view.owner);
+            scrub_grants(&mut view.grants);
+            for column in &mut view.columns {
+                scrub_grants(&mut column.grants);
+            }
+        }
+        let materialized_views = self.materialized_views_added.iter_mut().chain(&mut self.materialized_views_removed);
+        for view in materialized_views {
+            scrub_owner(&mut view.owner);
+            scrub_grants(&mut view.grants);
+            for column in &mut view.columns {
+                scrub_grants(&mut column.grants);
+            }
+        }
+        for view_diff in self.views_modified.iter_mut().chain(&mut self.materialized_views_modified) {
+            scrub_owners(&mut view_diff.old_owner, &mut view_diff.new_owner);
+            scrub_grants(&mut view_diff.old_grants);
+            scrub_grants(&mut view_diff.new_grants);
+            let columns = view_diff.columns_added.iter_mut().chain(&mut view_diff.columns_removed);
+            let columns = columns.chain(&mut view_diff.old_columns).chain(&mut view_diff.new_columns);
+            for column in columns {
+                scrub_grants(&mut column.grants);
+            }
+            for column_diff in &mut view_diff.columns_modified {
+                scrub_grants(&mut column_diff.old.grants);
+                scrub_grants(&mut column_diff.new.grants);
+            }
+            view_diff.columns_modified.retain(|c| c.old != c.new);
+        }
+        self.views_modified.retain(ViewDiff::has_changes);
+        self.materialized_views_modified.retain(ViewDiff::has_changes);
+
+        for sequence in self.sequences_added.iter_mut().chain(&mut self.sequences_removed) {
+            scrub_owner(&mut sequence.owner);
+            scrub_grants(&mut sequence.grants);
+        }
+        for sequence_diff in &mut self.sequences_modified {
+            let (old, new) = (&mut sequence_diff.old, &mut sequence_diff.new);
+            scrub_owners(&mut old.owner, &mut new.owner);
+            scrub_grants(&mut old.grants);
+            scrub_grants(&mut new.grants);
+        }
+        self.sequences_modified.retain(|s| s.old != s.new);
+
+        for routine in self.routines_added.iter_mut().chain(&mut self.routines_removed) {
+            scrub_owner(&mut routine.owner);
+            scrub_grants(&mut routine.grants);
+        }
+        for routine_diff in &mut self.routines_modified {
+            let (old, new) = (&mut routine_diff.old, &mut routine_diff.new);
+            scrub_owners(&mut old.owner, &mut new.owner);
+            scrub_grants(&mut old.grants);
+            scrub_grants(&mut new.grants);
+        }
+        self.routines_modified.retain(|r| r.old != r.new);
+
+        let privileges = self.schema_privileges_added.iter_mut().chain(&mut self.schema_privileges_removed);
+        for privileges in privileges {
+            scrub_grants(&mut privileges.grants);
+        }
+        for privileges_diff in &mut self.schema_privileges_modified {
+            scrub_grants(&mut privileges_diff.old.grants);
+            scrub_grants(&mut privileges_diff.new.grants);
+            if ignored(&privileges_diff.old.owner) || ignored(&privileges_diff.new.owner) {
+                privileges_diff.new.owner = privileges_diff.old.owner.clone();
+            }
+        }
+        self.schema_privileges_modified.retain(|p| p.old != p.new);
+    }
+
     pub fn has_changes(&self) -> bool {
-        !self.tables_added.is_empty()
+        !self.schemas_added.is_empty()
+            || !self.schemas_removed.is_empty()
+            || !self.tables_added.is_empty()
             || !self.tables_removed.is_empty()
             || !self.tables_modified.is_empty()
+            || !self.partitions_added.is_empty()
+            || !self.partitions_removed.is_empty()
+            || !self.sequences_added.is_empty()
+            || !self.sequences_removed.is_empty()
+            || !self.sequences_modified.is_empty()
+            || !self.enums_added.is_empty()
+            || !self.enums_removed.is_empty()
+            || !self.enums_modified.is_empty()
+            || !self.domains_added.is_empty()
+            || !self.domains_removed.is_empty()
+            || !self.domains_modified.is_empty()
+            || !self.composite_types_added.is_empty()
+            || !self.composite_types_removed.is_empty()
+            || !self.composite_types_modified.is_empty()
+            || !self.views_added.is_empty()
+            || !self.views_removed.is_empty()
+            || !self.views_modified.is_empty()
+            || !self.materialized_views_added.is_empty()
+            || !self.materialized_views_removed.is_empty()
+            || !self.materialized_views_modified.is_empty()
+            || !self.routines_added.is_empty()
+            || !self.routines_removed.is_empty()
+            || !self.routines_modified.is_empty()
+            || !self.event_triggers_added.is_empty()
+            || !self.event_triggers_removed.is_empty()
+            || !self.event_triggers_modified.is_empty()
+            || !self.schema_privileges_modified.is_empty()
+    }
+
+    /// Whether the diff contains changes that can lose data or break code
+    /// that uses the schema: dropped schemas, tables, partitions, columns,
+    /// sequences, views, materialized views, routines, types or enum labels,
+    /// column type changes other than widenings, regular columns becoming
+    /// generated, or repartitioned tables
+    pub fn has_destructive_changes(&self) -> bool {
+        !self.schemas_removed.is_empty()
+            || !self.tables_removed.is_empty()
+            || !self.partitions_removed.is_empty()
+            || !self.sequences_removed.is_empty()
+            || !self.views_removed.is_empty()
+            || !self.materialized_views_removed.is_empty()
+            || !self.routines_removed.is_empty()
+            || !self.enums_removed.is_empty()
+            || !self.domains_removed.is_empty()
+            || !self.composite_types_removed.is_empty()
+            || self.enums_modified.iter().any(|e| !e.labels_removed.is_empty())
+            || self.tables_modified.iter().any(|t| {
+                !t.columns_removed.is_empty()
+                    || t.partitioning_changed()
+                    || t.columns_modified.iter().any(|c| {
+                        c.lossy_type_change() || (c.old.generated.is_none() && c.new.generated.is_some())
+                    })
+            })
+    }
+
+    pub fn stats(&self) -> DiffStats {
+        let mut stats = DiffStats {
+            schemas_added: self.schemas_added.len(),
+            schemas_removed: self.schemas_removed.len(),
+            tables_added: self.tables_added.len(),
+            tables_removed: self.tables_removed.len(),
+            tables_modified: self.tables_modified.len(),
+            partitions_added: self.partitions_added.len(),
+            partitions_removed: self.partitions_removed.len(),
+            sequences_added: self.sequences_added.len(),
+            sequences_removed: self.sequences_removed.len(),
+            sequences_modified: self.sequences_modified.len(),
+            types_added: self.enums_added.len() + self.domains_added.len() + self.composite_types_added.len(),
+            types_removed: self.enums_removed.len()
+                + self.domains_removed.len()
+                + self.composite_types_removed.len(),
+            types_modified: self.enums_modified.len()
+                + self.domains_modified.len()
+                + self.composite_types_modified.len(),
+            views_added: self.views_added.len(),
+            views_removed: self.views_removed.len(),
+            views_modified: self.views_modified.len(),
+            materialized_views_added: self.materialized_views_added.len(),
+            materialized_views_removed: self.materialized_views_removed.len(),
+            materialized_views_modified: self.materialized_views_modified.len(),
+            routines_added: self.routines_added.len(),
+            routines_removed: self.routines_removed.len(),
+            routines_modified: self.routines_modified.len(),
+            event_triggers_added: self.event_triggers_added.len(),
+            event_triggers_removed: self.event_triggers_removed.len(),
+            event_triggers_modified: self.event_triggers_modified.len(),
+            ..DiffStats::default()
+        };
+
+        for table_diff in &self.tables_modified {
+            stats.columns_added += table_diff.columns_added.len();
+            stats.columns_removed += table_diff.columns_removed.len();
+            stats.columns_modified += table_diff.columns_modified.len();
+            stats.indexes_added += table_diff.indexes_added.len();
+            stats.indexes_removed += table_diff.indexes_removed.len();
+            stats.indexes_modified += table_diff.indexes_modified.len();
+            stats.constraints_added += table_diff.constraints_added.len();
+            stats.constraints_removed += table_diff.constraints_removed.len();
+            stats.constraints_modified += table_diff.constraints_modified.len();
+            stats.triggers_added += table_diff.triggers_added.len();
+            stats.triggers_removed += table_diff.triggers_removed.len();
+            stats.triggers_modified += table_diff.triggers_modified.len();
+            stats.policies_added += table_diff.policies_added.len();
+            stats.policies_removed += table_diff.policies_removed.len();
+            stats.policies_modified += table_diff.policies_modified.len();
+            stats.owners_changed += usize::from(table_diff.owner_changed());
+            stats.grants_added += table_diff.grants_added.len();
+            stats.grants_removed += table_diff.grants_removed.len();
+            for column_diff in &table_diff.columns_modified {
+                let (added, removed) = diff_grants(&column_diff.old.grants, &column_diff.new.grants);
+                stats.grants_added += added.len();
+                stats.grants_removed += removed.len();
+            }
+        }
+
+        let views = self.views_modified.iter().chain(&self.materialized_views_modified);
+        let columns = views.clone().flat_map(|v| &v.columns_modified).map(|c| (false, &c.old.grants, &c.new.grants));
+        let views = views.map(|v| (v.owner_changed(), &v.old_grants, &v.new_grants));
+        let sequences = self.sequences_modified.iter().map(|s| (s.owner_changed(), &s.old.grants, &s.new.grants));
+        let routines = self.routines_modified.iter().map(|r| (r.owner_changed(), &r.old.grants, &r.new.grants));
+        for (owner_changed, old_grants, new_grants) in views.chain(columns).chain(sequences).chain(routines) {
+            stats.owners_changed += usize::from(owner_changed);
+            let (added, removed) = diff_grants(old_grants, new_grants);
+            stats.grants_added += added.len();
+            stats.grants_removed += removed.len();
+        }
+        for privileges_diff in &self.schema_privileges_modified {
+            stats.owners_changed += usize::from(privileges_diff.owner_changed());
+            let (added, removed) = diff_grants(&privileges_diff.old.grants, &privileges_diff.new.grants);
+            stats.grants_added += added.len();
+            stats.grants_removed += removed.len();
+        }
+
+        stats
     }
 }
 
+/// Constraints of a table other than NOT NULL, which older snapshots of
+/// PostgreSQL 18 databases recorded alongside column nullability
+fn comparable_constraints(table: &Table) -> Vec<Constraint> {
+    table
+        .constraints
+        .iter()
+        .filter(|c| c.constraint_type != ConstraintType::NotNull)
+        .cloned()
+        .collect()
+}
+
 impl TableDiff {
+    /// A table that stays a partition inherits its columns from the parent,
+    /// along with their type, collation and order, so only the column
+    /// defaults, nullability and grants it keeps of its own are compared
     pub fn compare(old: &Table, new: &Table) -> Self {
-        let mut diff = TableDiff {
-            table_name: new.name.clone(),
-            columns_added: Vec::new(),
-            columns_removed: Vec::new(),
-            columns_modified: Vec::new(),
+        let (columns_added, columns_removed, columns_modified) = if old.is_partition() && new.is_partition() {
+            (Vec::new(), Vec::new(), diff_partition_columns(&old.columns, &new.columns))
+        } else {
+            diff_columns(&old.columns, &new.columns)
         };
+        let (indexes_added, indexes_removed, indexes_modified) = diff_indexes(&old.indexes, &new.indexes);
+        let (constraints_added, constraints_removed, constraints_modified) =
+            diff_by_key(&comparable_constraints(old), &comparable_constraints(new), |c| c.name.clone());
+        let (triggers_added, triggers_removed, triggers_modified) =
+            diff_by_key(&old.triggers, &new.triggers, |t| t.name.clone());
+        let (policies_added, policies_removed, policies_modified) =
+            diff_by_key(&old.policies, &new.policies, |p| p.name.clone());
+        let (grants_added, grants_removed) = diff_grants(&old.grants, &new.grants);
 
-        // Find added and modified columns
-        for new_col in &new.columns {
-            match old.columns.iter().find(|c| c.name == new_col.name) {
-                Some(old_col) => {
-                    if old_col != new_col {
-                        diff.columns_modified.push(ColumnDiff {
-                            column_name: new_col.name.clone(),
-                            old: old_col.clone(),
-                            new: new_col.clone(),
-                        });
-                    }
-                }
-                None => {
-                    diff.columns_added.push(new_col.clone());
-                }
-            }
+        TableDiff {
+            schema_name: new.schema.clone(),
+            table_name: new.name.clone(),
+            columns_added,
+            columns_removed,
+            columns_modified,
+            indexes_added,
+            indexes_removed,
+            indexes_modified,
+            constraints_added,
+            constraints_removed,
+            constraints_modified: constraints_modified
+                .into_iter()
+                .map(|(old, new)| ConstraintDiff {
+                    constraint_name: new.name.clone(),
+                    old,
+                    new,
+                })
+                .collect(),
+            triggers_added,
+            triggers_removed,
+            triggers_modified: triggers_modified
+                .into_iter()
+                .map(|(old, new)| TriggerDiff {
+                    trigger_name: new.name.clone(),
+                    old,
+                    new,
+                })
+                .collect(),
+            old_partitioning: old.partitioning.clone(),
+            new_partitioning: new.partitioning.clone(),
+            old_partition_of: old.partition_of.clone(),
+            new_partition_of: new.partition_of.clone(),
+            old_rls_enabled: old.rls_enabled,
+            new_rls_enabled: new.rls_enabled,
+            old_rls_forced: old.rls_forced,
+            new_rls_forced: new.rls_forced,
+            policies_added,
+            policies_removed,
+            policies_modified: policies_modified
+                .into_iter()
+                .map(|(old, new)| PolicyDiff {
+                    policy_name: new.name.clone(),
+                    old,
+                    new,
+                })
+                .collect(),
+            old_owner: old.owner.clone(),
+            new_owner: new.owner.clone(),
+            grants_added,
+            grants_removed,
         }
+    }
 
-        // Find removed columns
-        for old_col in &old.columns {
-            if !new.columns.iter().any(|c| c.name == old_col.name) {
-                diff.columns_removed.push(old_col.clone());
-            }
+    /// The table diff that takes the new table back to the old one
+    pub fn invert(&self) -> TableDiff {
+        TableDiff {
+            schema_name: self.schema_name.clone(),
+            table_name: self.table_name.clone(),
+            columns_added: self.columns_removed.clone(),
+            columns_removed: self.columns_added.clone(),
+            columns_modified: self.columns_modified.iter().map(ColumnDiff::invert).collect(),
+            indexes_added: self.indexes_removed.clone(),
+            indexes_removed: self.indexes_added.clone(),
+            indexes_modified: self.indexes_modified.iter().map(IndexDiff::invert).collect(),
+            constraints_added: self.constraints_removed.clone(),
+            constraints_removed: self.constraints_added.clone(),
+            constraints_modified: self.constraints_modified.iter().map(ConstraintDiff::invert).collect(),
+            triggers_added: self.triggers_removed.clone(),
+            triggers_removed: self.triggers_added.clone(),
+            triggers_modified: self.triggers_modified.iter().map(TriggerDiff::invert).collect(),
+            old_partitioning: self.new_partitioning.clone(),
+            new_partitioning: self.old_partitioning.clone(),
+            old_partition_of: self.new_partition_of.clone(),
+            new_partition_of: self.old_partition_of.clone(),
+            old_rls_enabled: self.new_rls_enabled,
+            new_rls_enabled: self.old_rls_enabled,
+            old_rls_forced: self.new_rls_forced,
+            new_rls_forced: self.old_rls_forced,
+            policies_added: self.policies_removed.clone(),
+            policies_removed: self.policies_added.clone(),
+            policies_modified: self.policies_modified.iter().map(PolicyDiff::invert).collect(),
+            old_owner: self.new_owner.clone(),
+            new_owner: self.old_owner.clone(),
+            grants_added: self.grants_removed.clone(),
+            grants_removed: self.grants_added.clone(),
         }
+    }
 
-        diff
+    pub fn owner_changed(&self) -> bool {
+        owner_changed(&self.old_owner, &self.new_owner)
+    }
+
+    /// Whether row-level security was switched on or off, or forced or
+    /// unforced, on this table
+    pub fn rls_switched(&self) -> bool {
+        self.old_rls_enabled != self.new_rls_enabled || self.old_rls_forced != self.new_rls_forced
+    }
+
+    /// Whether anything about row-level security changed, including policies
+    pub fn rls_changed(&self) -> bool {
+        self.rls_switched()
+            || !self.policies_added.is_empty()
+            || !self.policies_removed.is_empty()
+            || !self.policies_modified.is_empty()
+    }
+
+    /// Whether the table was partitioned differently, or became or stopped
+    /// being partitioned
+    pub fn partitioning_changed(&self) -> bool {
+        self.old_partitioning != self.new_partitioning
+    }
+
+    /// Whether the table was attached as or detached from a partition, or
+    /// its bound changed
+    pub fn partition_bound_changed(&self) -> bool {
+        self.old_partition_of != self.new_partition_of
+    }
+
+    /// Schema-qualified name of the table, e.g. `public.users`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema_name, self.table_name)
     }
 
     pub fn has_changes(&self) -> bool {
         !self.columns_added.is_empty()
             || !self.columns_removed.is_empty()
             || !self.columns_modified.is_empty()
+            || !self.indexes_added.is_empty()
+            || !self.indexes_removed.is_empty()
+            || !self.indexes_modified.is_empty()
+            || !self.constraints_added.is_empty()
+            || !self.constraints_removed.is_empty()
+            || !self.constraints_modified.is_empty()
+            || !self.triggers_added.is_empty()
+            || !self.triggers_removed.is_empty()
+            || !self.triggers_modified.is_empty()
+            || self.partitioning_changed()
+            || self.partition_bound_changed()
+            || self.rls_changed()
+            || self.owner_changed()
+            || !self.grants_added.is_empty()
+            || !self.grants_removed.is_empty()
+    }
+}
+
+impl ViewDiff {
+    pub fn compare(old: &View, new: &View) -> Self {
+        let (columns_added, columns_removed, columns_modified) = diff_columns(&old.columns, &new.columns);
+
+        ViewDiff {
+            schema_name: new.schema.clone(),
+            view_name: new.name.clone(),
+            old_definition: old.definition.clone(),
+            new_definition: new.definition.clone(),
+            columns_added,
+            columns_removed,
+            columns_modified,
+            indexes_added: Vec::new(),
+            indexes_removed: Vec::new(),
+            indexes_modified: Vec::new(),
+            old_indexes: Vec::new(),
+            new_indexes: Vec::new(),
+            old_owner: old.owner.clone(),
+            new_owner: new.owner.clone(),
+            old_grants: old.grants.clone(),
+            new_grants: new.grants.clone(),
+            old_columns: old.columns.clone(),
+            new_columns: new.columns.clone(),
+        }
+    }
+
+    pub fn compare_materialized(old: &MaterializedView, new: &MaterializedView) -> Self {
+        let (columns_added, columns_removed, columns_modified) = diff_columns(&old.columns, &new.columns);
+        let (indexes_added, indexes_removed, indexes_modified) = diff_indexes(&old.indexes, &new.indexes);
+
+        ViewDiff {
+            schema_name: new.schema.clone(),
+            view_name: new.name.clone(),
+            old_definition: old.definition.clone(),
+            new_definition: new.definition.clone(),
+            columns_added,
+            columns_removed,
+            columns_modified,
+            indexes_added,
+            indexes_removed,
+            indexes_modified,
+            old_indexes: old.indexes.clone(),
+            new_indexes: new.indexes.clone(),
+            old_owner: old.owner.clone(),
+            new_owner: new.owner.clone(),
+            old_grants: old.grants.clone(),
+            new_grants: new.grants.clone(),
+            old_columns: old.columns.clone(),
+            new_columns: new.columns.clone(),
+        }
+    }
+
+    /// The view diff that takes the new view back to the old one
+    pub fn invert(&self) -> ViewDiff {
+        ViewDiff {
+            schema_name: self.schema_name.clone(),
+            view_name: self.view_name.clone(),
+            old_definition: self.new_definition.clone(),
+            new_definition: self.old_definition.clone(),
+            columns_added: self.columns_removed.clone(),
+            columns_removed: self.columns_added.clone(),
+            columns_modified: self.columns_modified.iter().map(ColumnDiff::invert).collect(),
+            indexes_added: self.indexes_removed.clone(),
+            indexes_removed: self.indexes_added.clone(),
+            indexes_modified: self.indexes_modified.iter().map(IndexDiff::invert).collect(),
+            old_indexes: self.new_indexes.clone(),
+            new_indexes: self.old_indexes.clone(),
+            old_owner: self.new_owner.clone(),
+            new_owner: self.old_owner.clone(),
+            old_grants: self.new_grants.clone(),
+            new_grants: self.old_grants.clone(),
+            old_columns: self.new_columns.clone(),
+            new_columns: self.old_columns.clone(),
+        }
+    }
+
+    /// Schema-qualified name of the view, e.g. `public.active_users`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema_name, self.view_name)
+    }
+
+    pub fn definition_changed(&self) -> bool {
+        self.old_definition != self.new_definition
+    }
+
+    /// Whether the view must be dropped and recreated (anything beyond index,
+    /// owner and grant changes)
+    pub fn needs_rebuild(&self) -> bool {
+        self.definition_changed()
+            || !self.columns_added.is_empty()
+            || !self.columns_removed.is_empty()
+            || self.columns_modified.iter().any(|c| !c.only_grants_changed())
+    }
+
+    pub fn owner_changed(&self) -> bool {
+        owner_changed(&self.old_owner, &self.new_owner)
+    }
+
+    pub fn grants_changed(&self) -> bool {
+        self.old_grants != self.new_grants
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.definition_changed()
+            || !self.columns_added.is_empty()
+            || !self.columns_removed.is_empty()
+            || !self.columns_modified.is_empty()
+            || !self.indexes_added.is_empty()
+            || !self.indexes_removed.is_empty()
+            || !self.indexes_modified.is_empty()
+            || self.owner_changed()
+            || self.grants_changed()
+    }
+}
+
+impl SequenceDiff {
+    pub fn invert(&self) -> SequenceDiff {
+        SequenceDiff {
+            sequence_name: self.sequence_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+
+    pub fn owner_changed(&self) -> bool {
+        owner_changed(&self.old.owner, &self.new.owner)
+    }
+}
+
+impl EnumDiff {
+    pub fn compare(old: EnumType, new: EnumType) -> Self {
+        let labels_added: Vec<String> = new
+            .labels
+            .iter()
+            .filter(|l| !old.labels.contains(l))
+            .cloned()
+            .collect();
+        let labels_removed: Vec<String> = old
+            .labels
+            .iter()
+            .filter(|l| !new.labels.contains(l))
+            .cloned()
+            .collect();
+
+        let old_common: Vec<&String> = old.labels.iter().filter(|l| new.labels.contains(l)).collect();
+        let new_common: Vec<&String> = new.labels.iter().filter(|l| old.labels.contains(l)).collect();
+
+        EnumDiff {
+            type_name: new.qualified_name(),
+            labels_reordered: old_common != new_common,
+            labels_added,
+            labels_removed,
+            old,
+            new,
+        }
+    }
+
+    pub fn invert(&self) -> EnumDiff {
+        EnumDiff {
+            type_name: self.type_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+            labels_added: self.labels_removed.clone(),
+            labels_removed: self.labels_added.clone(),
+            labels_reordered: self.labels_reordered,
+        }
+    }
+
+    /// Whether the change can be applied with `ALTER TYPE ... ADD VALUE` alone
+    pub fn is_additive(&self) -> bool {
+        self.labels_removed.is_empty() && !self.labels_reordered
+    }
+}
+
+impl DomainDiff {
+    pub fn invert(&self) -> DomainDiff {
+        DomainDiff {
+            type_name: self.type_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+}
+
+impl CompositeTypeDiff {
+    pub fn invert(&self) -> CompositeTypeDiff {
+        CompositeTypeDiff {
+            type_name: self.type_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+}
+
+impl RoutineDiff {
+    pub fn invert(&self) -> RoutineDiff {
+        RoutineDiff {
+            signature: self.signature.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+
+    pub fn owner_changed(&self) -> bool {
+        owner_changed(&self.old.owner, &self.new.owner)
+    }
+}
+
+impl TriggerDiff {
+    pub fn invert(&self) -> TriggerDiff {
+        TriggerDiff {
+            trigger_name: self.trigger_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+}
+
+impl PolicyDiff {
+    pub fn invert(&self) -> PolicyDiff {
+        PolicyDiff {
+            policy_name: self.policy_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+
+    /// Whether the policy must be dropped and recreated: ALTER POLICY cannot
+    /// change its command or kind, or remove an expression
+    pub fn needs_recreate(&self) -> bool {
+        self.old.command != self.new.command
+            || self.old.permissive != self.new.permissive
+            || (self.old.using.is_some() && self.new.using.is_none())
+            || (self.old.with_check.is_some() && self.new.with_check.is_none())
+    }
+}
+
+impl SchemaPrivilegesDiff {
+    pub fn invert(&self) -> SchemaPrivilegesDiff {
+        SchemaPrivilegesDiff {
+            schema_name: self.schema_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+
+    pub fn owner_changed(&self) -> bool {
+        self.old.owner != self.new.owner
+    }
+}
+
+impl EventTriggerDiff {
+    pub fn invert(&self) -> EventTriggerDiff {
+        EventTriggerDiff {
+            trigger_name: self.trigger_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+}
+
+impl ColumnDiff {
+    pub fn compare(old: Column, new: Column) -> Self {
+        ColumnDiff {
+            column_name: new.name.clone(),
+            type_change: TypeChange::classify(&old.data_type, &new.data_type),
+            old,
+            new,
+        }
+    }
+
+    pub fn invert(&self) -> ColumnDiff {
+        ColumnDiff {
+            column_name: self.column_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+            type_change: self.type_change,
+        }
+    }
+
+    pub fn collation_changed(&self) -> bool {
+        self.old.collation != self.new.collation || self.old.collation_schema != self.new.collation_schema
+    }
+
+    /// Whether converting to the new type may fail or lose data: a different
+    /// type, or a length or precision that does not widen
+    pub fn lossy_type_change(&self) -> bool {
+        match self.type_change {
+            None => false,
+            Some(TypeChange::Type) => true,
+            Some(_) => !TypeChange::widens(&self.old.data_type, &self.new.data_type),
+        }
+    }
+
+    pub fn grants_changed(&self) -> bool {
+        self.old.grants != self.new.grants
+    }
+
+    /// Whether the column differs in nothing but its grants
+    pub fn only_grants_changed(&self) -> bool {
+        let old = Column {
+            grants: self.new.grants.clone(),
+            ..self.old.clone()
+        };
+        old == self.new
+    }
+}
+
+impl TypeChange {
+    /// Compares two `format_type` strings. Types that only differ inside their
+    /// parenthesized modifiers are length or precision changes.
+    pub fn classify(old: &str, new: &str) -> Option<TypeChange> {
+        if old == new {
+            return None;
+        }
+
+        let base = strip_modifiers(new);
+        if strip_modifiers(old) != base {
+            return Some(TypeChange::Type);
+        }
+
+        let type_name = base.split([' ', '[']).next().unwrap_or_default();
+        if matches!(type_name, "numeric" | "time" | "timestamp" | "interval") {
+            Some(TypeChange::Precision)
+        } else {
+            Some(TypeChange::Length)
+        }
+    }
+
+    /// Whether every value of type `old` fits `new` unchanged, for two types
+    /// that only differ in their modifiers: a longer or unlimited length, more
+    /// fractional seconds, or a numeric that keeps at least as many integer
+    /// digits and decimals. Array types compare their element type.
+    pub fn widens(old: &str, new: &str) -> bool {
+        let base = strip_modifiers(new);
+        let (old, new) = (type_modifiers(old), type_modifiers(new));
+
+        match base.trim_end_matches("[]") {
+            "character varying" | "bit varying" => match (old.first(), new.first()) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some(old), Some(new)) => new >= old,
+            },
+            // Fixed-length strings are padded with spaces, which compare equal
+            "character" => new.first() >= old.first(),
+            "numeric" => match (old.as_slice(), new.as_slice()) {
+                (_, []) => true,
+                ([], _) => false,
+                (old, new) => {
+                    let old_scale = old.get(1).copied().unwrap_or(0);
+                    let new_scale = new.get(1).copied().unwrap_or(0);
+                    new_scale >= old_scale && new[0] - new_scale >= old[0] - old_scale
+                }
+            },
+            // Without a precision, times keep all 6 fractional digits
+            "time without time zone" | "time with time zone" | "timestamp without time zone"
+            | "timestamp with time zone" | "interval" => {
+                new.first().copied().unwrap_or(6) >= old.first().copied().unwrap_or(6)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Numbers in the first parenthesized modifier, e.g. `[12, 4]` for `numeric(12,4)`
+fn type_modifiers(data_type: &str) -> Vec<i64> {
+    let Some((_, rest)) = data_type.split_once('(') else {
+        return Vec::new();
+    };
+    let list = rest.split(')').next().unwrap_or_default();
+    list.split(',').filter_map(|m| m.trim().parse().ok()).collect()
+}
+
+/// `timestamp(3) with time zone` -> `timestamp with time zone`
+pub fn strip_modifiers(data_type: &str) -> String {
+    let mut stripped = String::new();
+    let mut depth = 0;
+    for c in data_type.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+impl IndexDiff {
+    pub fn invert(&self) -> IndexDiff {
+        IndexDiff {
+            index_name: self.index_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+}
+
+impl ConstraintDiff {
+    pub fn invert(&self) -> ConstraintDiff {
+        ConstraintDiff {
+            constraint_name: self.constraint_name.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+        }
+    }
+
+    /// Whether the only change is a NOT VALID constraint becoming validated
+    pub fn is_validation_only(&self) -> bool {
+        self.old.not_valid
+            && !self.new.not_valid
+            && Constraint {
+                not_valid: false,
+                ..self.old.clone()
+            } == self.new
+    }
+}
+
+fn diff_columns(old: &[Column], new: &[Column]) -> (Vec<Column>, Vec<Column>, Vec<ColumnDiff>) {
+    let (added, removed, modified) = diff_by_key(old, new, |c| c.name.clone());
+    let modified = modified
+        .into_iter()
+        .map(|(old, new)| ColumnDiff::compare(old, new))
+        .collect();
+    (added, removed, modified)
+}
+
+/// Changes to the columns two partitions have in common, leaving out what
+/// they inherit from the parent
+fn diff_partition_columns(old: &[Column], new: &[Column]) -> Vec<ColumnDiff> {
+    let (_, _, modified) = diff_by_key(old, new, |c| c.name.clone());
+    modified
+        .into_iter()
+        .filter_map(|(old, new)| {
+            let new = Column {
+                data_type: old.data_type.clone(),
+                ordinal_position: old.ordinal_position,
+                collation: old.collation.clone(),
+                collation_schema: old.collation_schema.clone(),
+                ..new
+            };
+            (old != new).then(|| ColumnDiff::compare(old, new))
+        })
+        .collect()
+}
+
+fn diff_indexes(old: &[Index], new: &[Index]) -> (Vec<Index>, Vec<Index>, Vec<IndexDiff>) {
+    let (added, removed, modified) = diff_by_key(old, new, |i| i.name.clone());
+    let modified = modified
+        .into_iter()
+        .map(|(old, new)| IndexDiff {
+            index_name: new.name.clone(),
+            old,
+            new,
+        })
+        .collect();
+    (added, removed, modified)
+}
+
+/// Splits two grant lists into grants only in `new` (added) and grants only
+/// in `old` (removed). Gaining or losing `WITH GRANT OPTION` counts as both.
+pub fn diff_grants(old: &[Grant], new: &[Grant]) -> (Vec<Grant>, Vec<Grant>) {
+    let added = new.iter().filter(|g| !old.contains(g)).cloned().collect();
+    let removed = old.iter().filter(|g| !new.contains(g)).cloned().collect();
+    (added, removed)
+}
+
+/// Whether an object changed hands. Snapshots taken before owners were
+/// recorded have none, which is not counted as a change.
+fn owner_changed(old: &Option<String>, new: &Option<String>) -> bool {
+    matches!((old, new), (Some(old), Some(new)) if old != new)
+}
+
+/// Whether `name` matches a glob `pattern` where `*` matches any run of
+/// characters and `?` matches exactly one
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, tried)) => {
+                    p = star + 1;
+                    n = tried + 1;
+                    backtrack = Some((star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Splits two lists into items only in `new` (added), items only in `old`
+/// (removed), and (old, new) pairs that share a key but differ (modified).
+/// Each key is computed once, and matching is by hash, so large schemas
+/// diff in linear time.
+fn diff_by_key<T, K>(old: &[T], new: &[T], key: impl Fn(&T) -> K) -> (Vec<T>, Vec<T>, Vec<(T, T)>)
+where
+    T: Clone + PartialEq,
+    K: Eq + Hash,
+{
+    let old_keyed: Vec<(K, &T)> = old.iter().map(|item| (key(item), item)).collect();
+    let new_keyed: Vec<(K, &T)> = new.iter().map(|item| (key(item), item)).collect();
+
+    let mut old_by_key: HashMap<&K, &T> = HashMap::with_capacity(old_keyed.len());
+    for (key, item) in &old_keyed {
+        old_by_key.entry(key).or_insert(item);
+    }
+    let new_keys: HashSet<&K> = new_keyed.iter().map(|(key, _)| key).collect();
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for (key, new_item) in &new_keyed {
+        match old_by_key.get(key) {
+            Some(&old_item) => {
+                if old_item != *new_item {
+                    modified.push((old_item.clone(), (*new_item).clone()));
+                }
+            }
+            None => added.push((*new_item).clone()),
+        }
+    }
+
+    let removed = old_keyed
+        .iter()
+        .filter(|(key, _)| !new_keys.contains(key))
+        .map(|(_, item)| (*item).clone())
+        .collect();
+
+    (added, removed, modified)
+}
+
+/// Items by key; with duplicate keys the first one wins, as with a linear search
+pub fn index_by<'a, T, K: Eq + Hash>(items: &'a [T], key: impl Fn(&'a T) -> K) -> HashMap<K, &'a T> {
+    let mut index = HashMap::with_capacity(items.len());
+    for item in items {
+        index.entry(key(item)).or_insert(item);
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_same_type_is_no_change() {
+        assert_eq!(TypeChange::classify("character varying(50)", "character varying(50)"), None);
+    }
+
+    #[test]
+    fn classify_modifier_changes() {
+        assert_eq!(
+            TypeChange::classify("character varying(50)", "character varying(255)"),
+            Some(TypeChange::Length)
+        );
+        assert_eq!(TypeChange::classify("numeric(10,2)", "numeric(12,4)"), Some(TypeChange::Precision));
+        assert_eq!(
+            TypeChange::classify("timestamp(3) with time zone", "timestamp with time zone"),
+            Some(TypeChange::Precision)
+        );
+        assert_eq!(
+            TypeChange::classify("character varying(20)[]", "character varying(50)[]"),
+            Some(TypeChange::Length)
+        );
+    }
+
+    #[test]
+    fn classify_different_types() {
+        assert_eq!(TypeChange::classify("integer", "bigint"), Some(TypeChange::Type));
+        assert_eq!(TypeChange::classify("text", "text[]"), Some(TypeChange::Type));
+        assert_eq!(
+            TypeChange::classify("timestamp(3) with time zone", "timestamp(3) without time zone"),
+            Some(TypeChange::Type)
+        );
+    }
+
+    #[test]
+    fn widens_lengths() {
+        assert!(TypeChange::widens("character varying(50)", "character varying(255)"));
+        assert!(TypeChange::widens("character varying(50)", "character varying"));
+        assert!(!TypeChange::widens("character varying(255)", "character varying(50)"));
+        assert!(!TypeChange::widens("character varying", "character varying(50)"));
+        assert!(TypeChange::widens("character(2)", "character(5)"));
+        assert!(!TypeChange::widens("character(5)", "character(2)"));
+        assert!(TypeChange::widens("bit varying(8)", "bit varying(16)"));
+    }
+
+    #[test]
+    fn widens_numeric() {
+        assert!(TypeChange::widens("numeric(10,2)", "numeric(12,4)"));
+        assert!(TypeChange::widens("numeric(10,2)", "numeric"));
+        assert!(TypeChange::widens("numeric(8)", "numeric(10,2)"));
+        // Fewer integer digits
+        assert!(!TypeChange::widens("numeric(10,2)", "numeric(10,4)"));
+        // Fewer decimals
+        assert!(!TypeChange::widens("numeric(10,4)", "numeric(12,2)"));
+        assert!(!TypeChange::widens("numeric", "numeric(10,2)"));
+    }
+
+    #[test]
+    fn widens_fractional_seconds() {
+        assert!(TypeChange::widens("timestamp(3) with time zone", "timestamp with time zone"));
+        assert!(TypeChange::widens("timestamp(3) with time zone", "timestamp(6) with time zone"));
+        assert!(!TypeChange::widens("timestamp with time zone", "timestamp(3) with time zone"));
+        assert!(TypeChange::widens("time(0) without time zone", "time(2) without time zone"));
+        assert!(!TypeChange::widens("interval(6)", "interval(0)"));
+    }
+
+    #[test]
+    fn widens_array_elements() {
+        assert!(TypeChange::widens("character varying(20)[]", "character varying(50)[]"));
+        assert!(!TypeChange::widens("character varying(50)[]", "character varying(20)[]"));
+        assert!(TypeChange::widens("numeric(5,2)[]", "numeric(7,2)[]"));
+    }
+
+    #[test]
+    fn lossy_type_change_counts_only_narrowing() {
+        let column = |data_type: &str| Column {
+            name: "c".to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: 1,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants: Vec::new(),
+        };
+        let diff = |old: &str, new: &str| ColumnDiff::compare(column(old), column(new));
+
+        assert!(!diff("character varying(50)", "character varying(255)").lossy_type_change());
+        assert!(diff("character varying(255)", "character varying(50)").lossy_type_change());
+        assert!(diff("integer", "bigint").lossy_type_change());
+        assert!(!diff("integer", "integer").lossy_type_change());
+    }
+
+    #[test]
+    fn glob_matches_literal() {
+        assert!(glob_matches("ci_bot", "ci_bot"));
+        assert!(!glob_matches("ci_bot", "ci_bot2"));
+        assert!(!glob_matches("ci_bot2", "ci_bot"));
+    }
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(glob_matches("ci_*", "ci_"));
+        assert!(glob_matches("ci_*", "ci_1234"));
+        assert!(!glob_matches("ci_*", "app"));
+        assert!(glob_matches("events_????_??", "events_2024_03"));
+        assert!(!glob_matches("events_????_??", "events_2024_3"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("?", ""));
+    }
+
+    #[test]
+    fn glob_matches_backtracks() {
+        assert!(glob_matches("*_tmp", "a_tmp_b_tmp"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(!glob_matches("a*b*c", "aXbYbZ"));
+        assert!(glob_matches("**x", "yyx"));
+    }
+
+    fn column(name: &str, data_type: &str, position: i32) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: position,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants: Vec::new(),
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> Table {
+        Table {
+            name: name.to_string(),
+            schema: "public".to_string(),
+            columns,
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            partition_of: None,
+            rls_enabled: false,
+            rls_forced: false,
+            policies: Vec::new(),
+            owner: None,
+            grants: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn not_null_constraints_match_column_nullability() {
+        let id = Column {
+            is_nullable: false,
+            ..column("id", "integer", 1)
+        };
+        let before_18 = table("orders", vec![id]);
+        let mut from_18 = before_18.clone();
+        from_18.constraints.push(Constraint {
+            name: "orders_id_not_null".to_string(),
+            constraint_type: ConstraintType::NotNull,
+            columns: vec!["id".to_string()],
+            foreign_schema: None,
+            foreign_table: None,
+            foreign_columns: None,
+            on_update: None,
+            on_delete: None,
+            match_type: None,
+            deferrable: false,
+            initially_deferred: false,
+            not_valid: false,
+            definition: Some("NOT NULL id".to_string()),
+        });
+
+        assert!(!TableDiff::compare(&before_18, &from_18).has_changes());
+        assert!(!TableDiff::compare(&from_18, &before_18).has_changes());
+
+        let nullable = table("orders", vec![column("id", "integer", 1)]);
+        let diff = TableDiff::compare(&from_18, &nullable);
+        assert_eq!(diff.columns_modified.len(), 1);
+        assert!(diff.constraints_removed.is_empty());
+    }
+
+    #[test]
+    fn partition_columns_skip_what_the_parent_decides() {
+        let partition = |columns| Table {
+            partition_of: Some(PartitionBound {
+                parent_schema: "public".to_string(),
+                parent_table: "events".to_string(),
+                bound: "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')".to_string(),
+            }),
+            ..table("events_2024", columns)
+        };
+        let old = partition(vec![column("id", "integer", 1), column("kind", "text", 2)]);
+
+        // Type and order come from the parent
+        let retyped = partition(vec![column("id", "bigint", 2), column("kind", "text", 3)]);
+        assert!(!TableDiff::compare(&old, &retyped).has_changes());
+
+        // Defaults and nullability are the partition's own
+        let kind = Column {
+            is_nullable: false,
+            default_value: Some("'click'::text".to_string()),
+            ..column("kind", "text", 2)
+        };
+        let diff = TableDiff::compare(&old, &partition(vec![column("id", "integer", 1), kind]));
+        assert_eq!(diff.columns_modified.len(), 1);
+        assert_eq!(diff.columns_modified[0].column_name, "kind");
+        assert!(diff.columns_modified[0].type_change.is_none());
     }
 }
@@ -0,0 +1,805 @@
+use super::engine::{index_by, strip_modifiers};
+use crate::schema::models::{
+    format, Column, Constraint, ConstraintType, Index, Routine, Schema, Sequence, Table,
+};
+
+/// Copy of `older` with everything its format did not record taken from
+/// `newer`, so that comparing the two only reports what both captured.
+///
+/// Collections the older format did not capture at all are copied whole.
+/// Otherwise objects are matched the same way the diff matches them, and
+/// anything that only exists on one side is left alone and still shows as
+/// added or removed.
+pub fn fill_unknown(older: &Schema, newer: &Schema) -> Schema {
+    let mut schema = older.clone();
+    let format = older.format;
+
+    if format < format::VIEWS {
+        schema.views = newer.views.clone();
+        schema.materialized_views = newer.materialized_views.clone();
+    }
+    if format < format::ROUTINES {
+        schema.routines = newer.routines.clone();
+    }
+    if format < format::TRIGGERS {
+        schema.event_triggers = newer.event_triggers.clone();
+    }
+    if format < format::SEQUENCES {
+        schema.sequences = newer.sequences.clone();
+    }
+    if format < format::TYPES {
+        schema.enums = newer.enums.clone();
+        schema.domains = newer.domains.clone();
+        schema.composite_types = newer.composite_types.clone();
+    }
+
+    let newer_tables = index_by(&newer.tables, |t| (t.schema.as_str(), t.name.as_str()));
+    for table in &mut schema.tables {
+        if let Some(newer_table) = newer_tables.get(&(table.schema.as_str(), table.name.as_str())) {
+            fill_table(table, newer_table, format);
+        }
+    }
+
+    let newer_views = index_by(&newer.views, |v| (v.schema.as_str(), v.name.as_str()));
+    for view in &mut schema.views {
+        if let Some(newer_view) = newer_views.get(&(view.schema.as_str(), view.name.as_str())) {
+            fill_columns(&mut view.columns, &newer_view.columns, format);
+            if format < format::VIEW_PRIVILEGES {
+                view.owner = newer_view.owner.clone();
+                view.grants = newer_view.grants.clone();
+            }
+        }
+    }
+
+    let newer_views = index_by(&newer.materialized_views, |v| (v.schema.as_str(), v.name.as_str()));
+    for view in &mut schema.materialized_views {
+        if let Some(newer_view) = newer_views.get(&(view.schema.as_str(), view.name.as_str())) {
+            fill_columns(&mut view.columns, &newer_view.columns, format);
+            fill_indexes(&mut view.indexes, &newer_view.indexes, format);
+            if format < format::VIEW_PRIVILEGES {
+                view.owner = newer_view.owner.clone();
+                view.grants = newer_view.grants.clone();
+            }
+        }
+    }
+
+    if format < format::PRIVILEGES {
+        // Only for the schemas this side has; the rest show as created or dropped
+        let schema_names = schema.schema_names();
+        schema.schema_privileges = newer
+            .schema_privileges
+            .iter()
+            .filter(|p| schema_names.contains(&p.name))
+            .cloned()
+            .collect();
+
+        let newer_sequences = index_by(&newer.sequences, Sequence::qualified_name);
+        for sequence in &mut schema.sequences {
+            if let Some(newer_sequence) = newer_sequences.get(&sequence.qualified_name()) {
+                sequence.owner = newer_sequence.owner.clone();
+                sequence.grants = newer_sequence.grants.clone();
+            }
+        }
+
+        let newer_routines = index_by(&newer.routines, Routine::signature);
+        for routine in &mut schema.routines {
+            if let Some(newer_routine) = newer_routines.get(&routine.signature()) {
+                routine.owner = newer_routine.owner.clone();
+                routine.grants = newer_routine.grants.clone();
+            }
+        }
+    }
+
+    if format < format::ROUTINE_ATTRIBUTES {
+        let newer_routines = index_by(&newer.routines, Routine::signature);
+        for routine in &mut schema.routines {
+            if let Some(newer_routine) = newer_routines.get(&routine.signature()) {
+                fill_routine(routine, newer_routine);
+            }
+        }
+    }
+
+    schema
+}
+
+/// Attributes and full definition. `BEGIN ATOMIC` bodies used to be
+/// recorded as empty.
+fn fill_routine(routine: &mut Routine, newer: &Routine) {
+    if routine.body.is_empty() {
+        routine.body = newer.body.clone();
+    }
+    routine.strict = newer.strict;
+    routine.leakproof = newer.leakproof;
+    routine.parallel = newer.parallel.clone();
+    routine.cost = newer.cost;
+    routine.rows = newer.rows;
+    routine.config = newer.config.clone();
+
+    // The definition would recreate the newer routine, so it is only taken
+    // when nothing else tells them apart
+    let same = Routine {
+        owner: routine.owner.clone(),
+        grants: routine.grants.clone(),
+        definition: None,
+        ..newer.clone()
+    };
+    if *routine == same {
+        routine.definition = newer.definition.clone();
+    }
+}
+
+fn fill_table(table: &mut Table, newer: &Table, format: u32) {
+    fill_columns(&mut table.columns, &newer.columns, format);
+    fill_indexes(&mut table.indexes, &newer.indexes, format);
+    fill_constraints(&mut table.constraints, &newer.constraints, format);
+
+    // Only primary key, unique and foreign key constraints used to be captured
+    if format < format::CONSTRAINT_DEFINITIONS {
+        for constraint in &newer.constraints {
+            let uncaptured = matches!(
+                constraint.constraint_type,
+                ConstraintType::Check | ConstraintType::Exclusion | ConstraintType::NotNull
+            );
+            if !uncaptured || table.constraints.iter().any(|c| c.name == constraint.name) {
+                continue;
+            }
+
+            if constraint.constraint_type == ConstraintType::Exclusion {
+                for index in table.indexes.iter_mut().filter(|i| i.name == constraint.name) {
+                    index.backs_constraint = true;
+                }
+            }
+            table.constraints.push(constraint.clone());
+        }
+    }
+
+    if format < format::TRIGGERS {
+        table.triggers = newer.triggers.clone();
+    }
+
+    // Partitions used to be captured like any table, including the indexes
+    // and constraints they inherit from their parent
+    if format < format::PARTITIONS {
+        table.partitioning = newer.partitioning.clone();
+        table.partition_of = newer.partition_of.clone();
+        if table.is_partition() {
+            table.indexes.retain(|i| newer.indexes.iter().any(|n| n.name == i.name));
+            table.constraints.retain(|c| newer.constraints.iter().any(|n| n.name == c.name));
+        }
+    }
+
+    if format < format::ROW_SECURITY {
+        table.rls_enabled = newer.rls_enabled;
+        table.rls_forced = newer.rls_forced;
+        table.policies = newer.policies.clone();
+    }
+
+    if format < format::PRIVILEGES {
+        table.owner = newer.owner.clone();
+        table.grants = newer.grants.clone();
+    }
+}
+
+fn fill_columns(columns: &mut [Column], newer: &[Column], format: u32) {
+    for column in columns {
+        let Some(newer_column) = newer.iter().find(|c| c.name == column.name) else {
+            continue;
+        };
+
+        if format < format::SEQUENCES {
+            column.identity = newer_column.identity.clone();
+        }
+        if format < format::COLUMN_TYPES {
+            if is_legacy_type(&column.data_type, &newer_column.data_type) {
+                column.data_type = newer_column.data_type.clone();
+            }
+            column.collation = newer_column.collation.clone();
+        }
+        if format < format::COLLATION_SCHEMAS && column.collation == newer_column.collation {
+            column.collation_schema = newer_column.collation_schema.clone();
+        }
+        if format < format::GENERATED_COLUMNS {
+            column.generated = newer_column.generated.clone();
+        }
+        if format < format::PRIVILEGES {
+            column.grants = newer_column.grants.clone();
+        }
+    }
+}
+
+/// Whether `legacy` is how older snapshots recorded `data_type`: without
+/// length or precision, `ARRAY` for any array, and user-defined types
+/// always schema-qualified, or as `USER-DEFINED`
+fn is_legacy_type(legacy: &str, data_type: &str) -> bool {
+    legacy == strip_modifiers(data_type)
+        || (legacy == "ARRAY" && data_type.ends_with("[]"))
+        || legacy == "USER-DEFINED"
+        || legacy.ends_with(&format!(".{}", data_type))
+}
+
+/// Index method, key options, INCLUDE, predicate and validity
+fn fill_indexes(indexes: &mut [Index], newer: &[Index], format: u32) {
+    if format >= format::INDEXES {
+        return;
+    }
+
+    for index in indexes {
+        let Some(newer_index) = newer.iter().find(|i| i.name == index.name) else {
+            continue;
+        };
+
+        if index.columns == legacy_index_columns(newer_index) {
+            index.columns = newer_index.columns.clone();
+        }
+        index.method = newer_index.method.clone();
+        index.key_options = newer_index.key_options.clone();
+        index.include = newer_index.include.clone();
+        index.predicate = newer_index.predicate.clone();
+        index.is_valid = newer_index.is_valid;
+    }
+}
+
+/// Foreign key schema, actions and match type, deferrability, validity and
+/// key column order
+fn fill_constraints(constraints: &mut [Constraint], newer: &[Constraint], format: u32) {
+    if format >= format::FOREIGN_KEYS {
+        return;
+    }
+
+    for constraint in constraints {
+        let Some(newer_constraint) = newer.iter().find(|c| c.name == constraint.name) else {
+            continue;
+        };
+
+        // Key columns used to be recorded sorted by name
+        if constraint.columns == sorted(&newer_constraint.columns) {
+            constraint.columns = newer_constraint.columns.clone();
+        }
+        if constraint.foreign_columns == newer_constraint.foreign_columns.as_deref().map(sorted) {
+            constraint.foreign_columns = newer_constraint.foreign_columns.clone();
+        }
+        constraint.foreign_schema = newer_constraint.foreign_schema.clone();
+        constraint.on_update = newer_constraint.on_update.clone();
+        constraint.on_delete = newer_constraint.on_delete.clone();
+        constraint.match_type = newer_constraint.match_type.clone();
+        constraint.deferrable = newer_constraint.deferrable;
+        constraint.initially_deferred = newer_constraint.initially_deferred;
+        constraint.not_valid = newer_constraint.not_valid;
+    }
+}
+
+fn sorted(columns: &[String]) -> Vec<String> {
+    let mut columns = columns.to_vec();
+    columns.sort();
+    columns.dedup();
+    columns
+}
+
+/// Columns as recorded before index keys were: plain key columns followed by
+/// INCLUDE columns, with expression keys left out
+fn legacy_index_columns(index: &Index) -> Vec<String> {
+    index
+        .columns
+        .iter()
+        .zip(&index.key_options)
+        .filter(|(_, options)| !options.expression)
+        .map(|(column, _)| column.clone())
+        .chain(index.include.iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::engine::SchemaDiff;
+    use crate::schema::models::*;
+    use chrono::Utc;
+    use serde_json::Value;
+
+    fn grant(grantee: &str, privilege: &str) -> Grant {
+        Grant {
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable: false,
+        }
+    }
+
+    fn column(name: &str, data_type: &str, position: i32) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: position,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants: Vec::new(),
+        }
+    }
+
+    fn index(name: &str, columns: &[&str]) -> Index {
+        Index {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            is_unique: false,
+            is_primary: false,
+            method: Some("btree".to_string()),
+            key_options: columns
+                .iter()
+                .map(|_| IndexKeyOptions {
+                    expression: false,
+                    descending: false,
+                    nulls_first: false,
+                    opclass: None,
+                })
+                .collect(),
+            include: Vec::new(),
+            predicate: None,
+            is_valid: true,
+            backs_constraint: false,
+        }
+    }
+
+    fn constraint(name: &str, constraint_type: ConstraintType, columns: &[&str]) -> Constraint {
+        Constraint {
+            name: name.to_string(),
+            constraint_type,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            foreign_schema: None,
+            foreign_table: None,
+            foreign_columns: None,
+            on_update: None,
+            on_delete: None,
+            match_type: None,
+            deferrable: false,
+            initially_deferred: false,
+            not_valid: false,
+            definition: None,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> Table {
+        Table {
+            name: name.to_string(),
+            schema: "public".to_string(),
+            columns,
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            partition_of: None,
+            rls_enabled: false,
+            rls_forced: false,
+            policies: Vec::new(),
+            owner: Some("app".to_string()),
+            grants: Vec::new(),
+        }
+    }
+
+    /// A schema with everything that older formats did not record set to
+    /// something other than its default
+    fn current_schema() -> Schema {
+        let mut orders = table(
+            "orders",
+            vec![
+                Column {
+                    identity: Some(Identity::Always),
+                    ..column("id", "bigint", 1)
+                },
+                Column {
+                    collation: Some("C".to_string()),
+                    collation_schema: Some("pg_catalog".to_string()),
+                    grants: vec![grant("auditor", "SELECT")],
+                    ..column("code", "character varying(50)", 2)
+                },
+                column("tags", "text[]", 3),
+                column("customer_id", "integer", 4),
+                column("region", "integer", 5),
+                Column {
+                    generated: Some(GeneratedColumn {
+                        kind: GeneratedKind::Stored,
+                        expression: "upper((code)::text)".to_string(),
+                    }),
+                    ..column("upper_code", "text", 6)
+                },
+                column("amount", "numeric(12,4)", 7),
+            ],
+        );
+        orders.indexes = vec![
+            Index {
+                is_unique: true,
+                is_primary: true,
+                backs_constraint: true,
+                ..index("orders_pkey", &["id"])
+            },
+            Index {
+                method: Some("gin".to_string()),
+                ..index("orders_tags_idx", &["tags"])
+            },
+            Index {
+                include: vec!["amount".to_string()],
+                predicate: Some("(amount > (0)::numeric)".to_string()),
+                is_valid: false,
+                ..index("orders_code_idx", &["code"])
+            },
+            Index {
+                key_options: vec![
+                    IndexKeyOptions {
+                        expression: true,
+                        descending: true,
+                        nulls_first: true,
+                        opclass: None,
+                    },
+                    IndexKeyOptions {
+                        expression: false,
+                        descending: false,
+                        nulls_first: false,
+                        opclass: Some("text_pattern_ops".to_string()),
+                    },
+                ],
+                ..index("orders_lower_code_idx", &["lower((code)::text)", "code"])
+            },
+        ];
+        orders.constraints = vec![
+            constraint("orders_pkey", ConstraintType::PrimaryKey, &["id"]),
+            Constraint {
+                foreign_schema: Some("public".to_string()),
+                foreign_table: Some("customers".to_string()),
+                foreign_columns: Some(vec!["region".to_string(), "id".to_string()]),
+                on_update: Some(ForeignKeyAction::Cascade),
+                on_delete: Some(ForeignKeyAction::SetNull),
+                match_type: Some(ForeignKeyMatch::Full),
+                deferrable: true,
+                initially_deferred: true,
+                not_valid: true,
+                ..constraint("orders_customer_fkey", ConstraintType::ForeignKey, &["region", "customer_id"])
+            },
+            Constraint {
+                definition: Some("CHECK ((amount > (0)::numeric))".to_string()),
+                ..constraint("orders_amount_check", ConstraintType::Check, &["amount"])
+            },
+        ];
+        orders.triggers = vec![Trigger {
+            name: "orders_audit".to_string(),
+            timing: TriggerTiming::After,
+            events: vec!["INSERT".to_string()],
+            for_each_row: true,
+            condition: None,
+            function: "public.audit()".to_string(),
+            enabled: TriggerEnabled::Origin,
+            definition: "CREATE TRIGGER orders_audit AFTER INSERT ON public.orders FOR EACH ROW EXECUTE FUNCTION audit()"
+                .to_string(),
+        }];
+        orders.rls_enabled = true;
+        orders.rls_forced = true;
+        orders.policies = vec![Policy {
+            name: "own_orders".to_string(),
+            command: PolicyCommand::Select,
+            permissive: true,
+            roles: vec!["public".to_string()],
+            using: Some("(customer_id = 1)".to_string()),
+            with_check: None,
+        }];
+        orders.grants = vec![grant("reporting", "SELECT")];
+
+        let mut customers = table("customers", vec![column("id", "integer", 1), column("region", "integer", 2)]);
+        customers.indexes = vec![Index {
+            is_unique: true,
+            backs_constraint: true,
+            ..index("customers_region_id_key", &["region", "id"])
+        }];
+        customers.constraints = vec![constraint(
+            "customers_region_id_key",
+            ConstraintType::Unique,
+            &["region", "id"],
+        )];
+
+        let mut events = table("events", vec![column("at", "timestamp(3) with time zone", 1)]);
+        events.partitioning = Some(Partitioning {
+            strategy: PartitionStrategy::Range,
+            key: "at".to_string(),
+        });
+        let mut events_2024 = table("events_2024", vec![column("at", "timestamp(3) with time zone", 1)]);
+        events_2024.partition_of = Some(PartitionBound {
+            parent_schema: "public".to_string(),
+            parent_table: "events".to_string(),
+            bound: "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')".to_string(),
+        });
+
+        Schema {
+            schemas: vec!["public".to_string()],
+            tables: vec![orders, customers, events, events_2024],
+            sequences: vec![Sequence {
+                name: "invoice_no".to_string(),
+                schema: "public".to_string(),
+                data_type: "bigint".to_string(),
+                start_value: 1,
+                increment: 1,
+                min_value: 1,
+                max_value: i64::MAX,
+                cycle: false,
+                owned_by: None,
+                owner: Some("app".to_string()),
+                grants: vec![grant("reporting", "USAGE")],
+            }],
+            enums: vec![EnumType {
+                name: "mood".to_string(),
+                schema: "public".to_string(),
+                labels: vec!["sad".to_string(), "happy".to_string()],
+            }],
+            domains: vec![Domain {
+                name: "positive".to_string(),
+                schema: "public".to_string(),
+                base_type: "integer".to_string(),
+                default_value: None,
+                not_null: true,
+                constraints: Vec::new(),
+            }],
+            composite_types: vec![CompositeType {
+                name: "pair".to_string(),
+                schema: "public".to_string(),
+                attributes: vec![CompositeAttribute {
+                    name: "a".to_string(),
+                    data_type: "integer".to_string(),
+                }],
+            }],
+            views: vec![View {
+                name: "big_orders".to_string(),
+                schema: "public".to_string(),
+                definition: "SELECT id FROM orders".to_string(),
+                columns: vec![Column {
+                    collation: Some("C".to_string()),
+                    collation_schema: Some("pg_catalog".to_string()),
+                    grants: vec![grant("auditor", "SELECT")],
+                    ..column("code", "character varying(50)", 1)
+                }],
+                owner: Some("app".to_string()),
+                grants: vec![grant("reporting", "SELECT")],
+            }],
+            materialized_views: vec![MaterializedView {
+                name: "order_totals".to_string(),
+                schema: "public".to_string(),
+                definition: "SELECT sum(amount) AS total FROM orders".to_string(),
+                columns: vec![column("total", "numeric(12,4)", 1)],
+                indexes: vec![Index {
+                    method: Some("brin".to_string()),
+                    ..index("order_totals_total_idx", &["total"])
+                }],
+                owner: Some("app".to_string()),
+                grants: vec![grant("reporting", "SELECT")],
+            }],
+            routines: vec![Routine {
+                name: "recent".to_string(),
+                schema: "public".to_string(),
+                kind: RoutineKind::Function,
+                argument_types: "integer".to_string(),
+                arguments: "n integer".to_string(),
+                return_type: Some("SETOF orders".to_string()),
+                language: "sql".to_string(),
+                volatility: Volatility::Stable,
+                security_definer: true,
+                body: "BEGIN ATOMIC\n SELECT * FROM orders LIMIT n;\nEND".to_string(),
+                strict: true,
+                leakproof: true,
+                parallel: Some(ParallelSafety::Safe),
+                cost: Some(50.0),
+                rows: Some(20.0),
+                config: vec!["search_path=public, pg_temp".to_string()],
+                definition: Some("CREATE OR REPLACE FUNCTION public.recent(n integer) ...".to_string()),
+                owner: Some("app".to_string()),
+                grants: vec![grant("public", "EXECUTE")],
+            }],
+            event_triggers: vec![EventTrigger {
+                name: "no_drops".to_string(),
+                event: "sql_drop".to_string(),
+                tags: Vec::new(),
+                function: "public.no_drops()".to_string(),
+                enabled: TriggerEnabled::Origin,
+            }],
+            schema_privileges: vec![SchemaPrivileges {
+                name: "public".to_string(),
+                owner: "app".to_string(),
+                grants: vec![grant("public", "USAGE")],
+            }],
+            format: format::CURRENT,
+            captured_at: Utc::now(),
+        }
+    }
+
+    /// Loads the current schema as a snapshot of `format` would have stored
+    /// it, once `strip` has removed what that format did not write
+    fn older_snapshot(format: u32, strip: impl Fn(&mut Value)) -> Schema {
+        let mut value = serde_json::to_value(current_schema()).unwrap();
+        strip(&mut value);
+        value["format"] = format.into();
+        Schema::from_json(&value.to_string()).unwrap()
+    }
+
+    /// Checks that an older snapshot of the current schema compares equal
+    /// to it both ways
+    fn assert_unknown_when_absent(format: u32, strip: impl Fn(&mut Value)) {
+        let schema = current_schema();
+        let older = older_snapshot(format, strip);
+
+        assert!(
+            !SchemaDiff::compare(&older, &schema).has_changes(),
+            "{:#?}",
+            SchemaDiff::compare(&older, &schema)
+        );
+        assert!(!SchemaDiff::compare(&schema, &older).has_changes());
+    }
+
+    /// Every object reached by following `path` through nested arrays,
+    /// e.g. `["tables", "columns"]` for every column of every table
+    fn each<'a>(value: &'a mut Value, path: &[&str]) -> Vec<&'a mut Value> {
+        let Some((first, rest)) = path.split_first() else {
+            return vec![value];
+        };
+        match value.get_mut(*first).and_then(Value::as_array_mut) {
+            Some(items) => items.iter_mut().flat_map(|item| each(item, rest)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn remove(value: &mut Value, path: &[&str], field: &str) {
+        for object in each(value, path) {
+            object.as_object_mut().unwrap().remove(field);
+        }
+    }
+
+    /// Fields older snapshots lack, as (the newest format without the field,
+    /// path to the objects that hold it, field)
+    const ABSENT_FIELDS: &[(u32, &[&str], &str)] = &[
+        (format::TABLES, &[], "views"),
+        (format::TABLES, &[], "materialized_views"),
+        (format::TABLES, &[], "schemas"),
+        (format::VIEWS, &[], "routines"),
+        (format::ROUTINES, &[], "event_triggers"),
+        (format::ROUTINES, &["tables"], "triggers"),
+        (format::TRIGGERS, &[], "sequences"),
+        (format::TRIGGERS, &["tables", "columns"], "identity"),
+        (format::SEQUENCES, &[], "enums"),
+        (format::SEQUENCES, &[], "domains"),
+        (format::SEQUENCES, &[], "composite_types"),
+        (format::TYPES, &["tables", "columns"], "collation"),
+        (format::COLUMN_TYPES, &["tables", "columns"], "generated"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "foreign_schema"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "on_update"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "on_delete"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "match_type"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "deferrable"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "initially_deferred"),
+        (format::GENERATED_COLUMNS, &["tables", "constraints"], "not_valid"),
+        (format::CONSTRAINT_DEFINITIONS, &["tables", "indexes"], "method"),
+        (format::CONSTRAINT_DEFINITIONS, &["materialized_views", "indexes"], "method"),
+        (format::CONSTRAINT_DEFINITIONS, &["tables", "indexes"], "key_options"),
+        (format::CONSTRAINT_DEFINITIONS, &["materialized_views", "indexes"], "key_options"),
+        (format::CONSTRAINT_DEFINITIONS, &["tables", "indexes"], "include"),
+        (format::CONSTRAINT_DEFINITIONS, &["materialized_views", "indexes"], "include"),
+        (format::CONSTRAINT_DEFINITIONS, &["tables", "indexes"], "predicate"),
+        (format::CONSTRAINT_DEFINITIONS, &["materialized_views", "indexes"], "predicate"),
+        (format::CONSTRAINT_DEFINITIONS, &["tables", "indexes"], "is_valid"),
+        (format::CONSTRAINT_DEFINITIONS, &["materialized_views", "indexes"], "is_valid"),
+        (format::INDEXES, &["tables"], "partitioning"),
+        (format::INDEXES, &["tables"], "partition_of"),
+        (format::PARTITIONS, &["tables"], "rls_enabled"),
+        (format::PARTITIONS, &["tables"], "rls_forced"),
+        (format::PARTITIONS, &["tables"], "policies"),
+        (format::ROW_SECURITY, &[], "schema_privileges"),
+        (format::ROW_SECURITY, &["tables"], "owner"),
+        (format::ROW_SECURITY, &["tables"], "grants"),
+        (format::ROW_SECURITY, &["sequences"], "owner"),
+        (format::ROW_SECURITY, &["sequences"], "grants"),
+        (format::ROW_SECURITY, &["routines"], "owner"),
+        (format::ROW_SECURITY, &["routines"], "grants"),
+        (format::ROW_SECURITY, &["tables", "columns"], "grants"),
+        (format::ROW_SECURITY, &["views", "columns"], "grants"),
+        (format::PRIVILEGES, &["tables", "indexes"], "backs_constraint"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "strict"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "leakproof"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "parallel"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "cost"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "rows"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "config"),
+        (format::CONSTRAINT_INDEXES, &["routines"], "definition"),
+        (format::ROUTINE_ATTRIBUTES, &["views"], "owner"),
+        (format::ROUTINE_ATTRIBUTES, &["views"], "grants"),
+        (format::ROUTINE_ATTRIBUTES, &["materialized_views"], "owner"),
+        (format::ROUTINE_ATTRIBUTES, &["materialized_views"], "grants"),
+        (format::VIEW_PRIVILEGES, &["tables", "columns"], "collation_schema"),
+        (format::VIEW_PRIVILEGES, &["views", "columns"], "collation_schema"),
+    ];
+
+    #[test]
+    fn fields_older_formats_lack() {
+        let schema = current_schema();
+        for &(format, path, field) in ABSENT_FIELDS {
+            let older = older_snapshot(format, |v| remove(v, path, field));
+            let row = format!("{} of {:?} at format {}", field, path, format);
+
+            assert!(!SchemaDiff::compare(&older, &schema).has_changes(), "{}", row);
+            assert!(!SchemaDiff::compare(&schema, &older).has_changes(), "{}", row);
+        }
+    }
+
+    #[test]
+    fn column_legacy_data_type() {
+        // Recorded without modifiers, and as `ARRAY` for any array
+        assert_unknown_when_absent(format::TYPES, |v| {
+            for column in each(v, &["tables", "columns"]) {
+                let data_type = column["data_type"].as_str().unwrap().to_string();
+                column["data_type"] = if data_type.ends_with("[]") {
+                    "ARRAY".into()
+                } else {
+                    strip_modifiers(&data_type).into()
+                };
+            }
+        });
+    }
+
+    #[test]
+    fn constraint_sorted_columns() {
+        // Key columns were recorded sorted by name
+        assert_unknown_when_absent(format::GENERATED_COLUMNS, |v| {
+            for constraint in each(v, &["tables", "constraints"]) {
+                for key in ["columns", "foreign_columns"] {
+                    if let Some(columns) = constraint[key].as_array_mut() {
+                        columns.sort_by_key(|c| c.as_str().unwrap().to_string());
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn constraint_definition() {
+        // Check constraints were not captured at all
+        assert_unknown_when_absent(format::FOREIGN_KEYS, |v| {
+            for table in each(v, &["tables"]) {
+                let constraints = table["constraints"].as_array_mut().unwrap();
+                constraints.retain(|c| c["constraint_type"] != "Check");
+            }
+            remove(v, &["tables", "constraints"], "definition");
+        });
+    }
+
+    #[test]
+    fn index_legacy_columns() {
+        // Expression keys were left out and INCLUDE columns listed as keys
+        assert_unknown_when_absent(format::CONSTRAINT_DEFINITIONS, |v| {
+            for index in each(v, &["tables", "indexes"]) {
+                let legacy: Index = serde_json::from_value(index.clone()).unwrap();
+                index["columns"] = legacy_index_columns(&legacy).into();
+            }
+        });
+    }
+
+    #[test]
+    fn routine_atomic_body() {
+        // BEGIN ATOMIC bodies were recorded as empty
+        assert_unknown_when_absent(format::CONSTRAINT_INDEXES, |v| {
+            for routine in each(v, &["routines"]) {
+                routine["body"] = "".into();
+            }
+            remove(v, &["routines"], "definition");
+        });
+    }
+
+    #[test]
+    fn pre_format_snapshot() {
+        // Placed by the newest field it contains, here row-level security
+        let schema = current_schema();
+        let mut value = serde_json::to_value(&schema).unwrap();
+        for field in ["format", "schema_privileges"] {
+            remove(&mut value, &[], field);
+        }
+        remove(&mut value, &["tables", "indexes"], "backs_constraint");
+        let older = Schema::from_json(&value.to_string()).unwrap();
+
+        assert_eq!(older.format, format::ROW_SECURITY);
+        assert!(older.tables[0].indexes[0].backs_constraint);
+        assert!(!SchemaDiff::compare(&older, &schema).has_changes());
+    }
+}
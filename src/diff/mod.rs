@@ -1 +1,3 @@
 pub mod engine;
+pub mod legacy;
+pub mod text;
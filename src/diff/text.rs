@@ -0,0 +1,124 @@
+/// One line of a line-based text diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineChange<'a> {
+    Unchanged(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+/// Computes a line-based diff of two texts using the longest common subsequence.
+///
+/// The LCS table takes time and memory proportional to the product of the
+/// line counts, so lines shared at the start and end are matched up front
+/// and only the changed middle goes through it.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<LineChange<'a>> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(o, n)| o == n)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+
+    let mut changes: Vec<LineChange> = old_lines[..prefix].iter().map(|l| LineChange::Unchanged(l)).collect();
+    changes.extend(diff_middle(
+        &old_lines[prefix..old_lines.len() - suffix],
+        &new_lines[prefix..new_lines.len() - suffix],
+    ));
+    changes.extend(old_lines[old_lines.len() - suffix..].iter().map(|l| LineChange::Unchanged(l)));
+
+    changes
+}
+
+fn diff_middle<'a>(old_lines: &[&'a str], new_lines: &[&'a str]) -> Vec<LineChange<'a>> {
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] = length of the LCS of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            changes.push(LineChange::Unchanged(old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            changes.push(LineChange::Removed(old_lines[i]));
+            i += 1;
+        } else {
+            changes.push(LineChange::Added(new_lines[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old_lines[i..].iter().map(|l| LineChange::Removed(l)));
+    changes.extend(new_lines[j..].iter().map(|l| LineChange::Added(l)));
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LineChange::{Added, Removed, Unchanged};
+
+    #[test]
+    fn unchanged_text() {
+        assert_eq!(diff_lines("a\nb", "a\nb"), vec![Unchanged("a"), Unchanged("b")]);
+    }
+
+    #[test]
+    fn inserted_lines() {
+        assert_eq!(
+            diff_lines("a\nc", "a\nb\nc\nd"),
+            vec![Unchanged("a"), Added("b"), Unchanged("c"), Added("d")]
+        );
+    }
+
+    #[test]
+    fn deleted_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "b\nd"),
+            vec![Removed("a"), Unchanged("b"), Removed("c"), Unchanged("d")]
+        );
+    }
+
+    #[test]
+    fn replaced_lines() {
+        assert_eq!(
+            diff_lines("select 1\nfrom a\nwhere x", "select 1\nfrom b\nwhere x"),
+            vec![Unchanged("select 1"), Removed("from a"), Added("from b"), Unchanged("where x")]
+        );
+    }
+
+    #[test]
+    fn repeated_lines() {
+        assert_eq!(
+            diff_lines("a\nb\na", "a\na"),
+            vec![Unchanged("a"), Removed("b"), Unchanged("a")]
+        );
+    }
+
+    #[test]
+    fn empty_inputs() {
+        assert_eq!(diff_lines("", ""), vec![]);
+        assert_eq!(diff_lines("", "a\nb"), vec![Added("a"), Added("b")]);
+        assert_eq!(diff_lines("a\nb", ""), vec![Removed("a"), Removed("b")]);
+    }
+}
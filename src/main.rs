@@ -18,11 +18,21 @@ async fn main() -> Result<()> {
         Commands::Init { connection } => {
             commands::init::execute(connection).await?;
         }
-        Commands::Snapshot { name } => {
-            commands::snapshot::execute(name).await?;
+        Commands::Snapshot { name, write_lock } => {
+            commands::snapshot::execute(name, write_lock).await?;
         }
-        Commands::Diff { from, to } => {
-            commands::diff::execute(from, to).await?;
+        Commands::Diff { from, to, format, sql, rollback } => {
+            commands::diff::execute(from, to, format, sql, rollback).await?;
+        }
+        Commands::Check { baseline, against, fail_on, format } => {
+            let code = match commands::check::execute(baseline, against, fail_on, format).await {
+                Ok(code) => code,
+                Err(err) => {
+                    eprintln!("Error: {:?}", err);
+                    commands::check::EXIT_ERROR
+                }
+            };
+            std::process::exit(code);
         }
         Commands::History { limit } => {
             commands::history::execute(limit).await?;
@@ -0,0 +1,174 @@
+//! Machine-readable JSON rendering of a [`SchemaDiff`].
+//!
+//! The document has this shape (version 2):
+//!
+//! ```text
+//! {
+//!   "format_version": 2,
+//!   "from": "latest",            // reference the diff starts from
+//!   "to": "current",             // reference the diff ends at
+//!   "has_changes": true,
+//!   "stats": { "tables_added": 1, "columns_modified": 2, ... },
+//!   "diff": {
+//!     "schemas_added": ["audit"],
+//!     "schemas_removed": [],
+//!     "tables_added": [Table, ...],     // full table definitions
+//!     "tables_removed": [Table, ...],
+//!     "tables_modified": [
+//!       {
+//!         "schema_name": "public",
+//!         "table_name": "users",
+//!         "columns_added": [Column, ...],
+//!         "columns_removed": [Column, ...],
+//!         "columns_modified": [
+//!           {
+//!             "column_name": "...",
+//!             "old": Column,
+//!             "new": Column,
+//!             "type_change": "Length" | "Precision" | "Type" | null
+//!           }
+//!         ],
+//!         "indexes_added" / "indexes_removed" / "indexes_modified": ...,
+//!         "constraints_added" / "constraints_removed" / "constraints_modified": ...,
+//!         "triggers_added" / "triggers_removed": [Trigger, ...],
+//!         "triggers_modified": [{ "trigger_name": "...", "old": Trigger, "new": Trigger }],
+//!         "old_partitioning" / "new_partitioning": Partitioning | null,
+//!         "old_partition_of" / "new_partition_of": PartitionBound | null,
+//!         "old_rls_enabled" / "new_rls_enabled" / "old_rls_forced" / "new_rls_forced": bool,
+//!         "policies_added" / "policies_removed": [Policy, ...],
+//!         "policies_modified": [{ "policy_name": "...", "old": Policy, "new": Policy }],
+//!         "old_owner" / "new_owner": "app" | null,
+//!         "grants_added" / "grants_removed": [Grant, ...]
+//!       }
+//!     ],
+//!     "partitions_added" / "partitions_removed": [Table, ...],  // with "partition_of" set
+//!     "sequences_added" / "sequences_removed": [Sequence, ...],
+//!     "sequences_modified": [{ "sequence_name": "public.s", "old": Sequence, "new": Sequence }],
+//!     "enums_added" / "enums_removed": [EnumType, ...],
+//!     "enums_modified": [
+//!       {
+//!         "type_name": "public.mood",
+//!         "old": EnumType,
+//!         "new": EnumType,
+//!         "labels_added": ["happy"],
+//!         "labels_removed": [],
+//!         "labels_reordered": false
+//!       }
+//!     ],
+//!     "domains_added" / "domains_removed" / "domains_modified": ...,
+//!     "composite_types_added" / "composite_types_removed" / "composite_types_modified": ...,
+//!     "views_added" / "views_removed": [View, ...],
+//!     "views_modified": [
+//!       {
+//!         "schema_name": "public",
+//!         "view_name": "active_users",
+//!         "old_definition": "...",
+//!         "new_definition": "...",
+//!         "columns_added" / "columns_removed" / "columns_modified": ...,
+//!         "indexes_added" / "indexes_removed" / "indexes_modified": ...,  // matviews only
+//!         "old_owner" / "new_owner": "app" | null,
+//!         "old_grants" / "new_grants": [Grant, ...]
+//!       }
+//!     ],
+//!     "materialized_views_added" / "materialized_views_removed": [MaterializedView, ...],
+//!     "materialized_views_modified": [ ...same shape as views_modified... ],
+//!     "routines_added" / "routines_removed": [Routine, ...],
+//!     "routines_modified": [{ "signature": "public.f(integer)", "old": Routine, "new": Routine }],
+//!     "event_triggers_added" / "event_triggers_removed": [EventTrigger, ...],
+//!     "event_triggers_modified": [{ "trigger_name": "...", "old": EventTrigger, "new": EventTrigger }],
+//!     "schema_privileges_added" / "schema_privileges_removed": [SchemaPrivileges, ...],
+//!     "schema_privileges_modified": [{ "schema_name": "audit", "old": SchemaPrivileges, "new": SchemaPrivileges }]
+//!   }
+//! }
+//! ```
+//!
+//! `Table`, `Sequence`, `EnumType`, `Domain`, `CompositeType`, `View`,
+//! `MaterializedView`, `Routine`, `Column`, `Index`, `Constraint`, `Trigger`,
+//! `Policy`, `EventTrigger`, `Grant` and `SchemaPrivileges` use the same field
+//! names as the snapshot model in `schema::models`. `format_version` is bumped
+//! whenever an existing field is renamed, removed or changes meaning; adding
+//! fields does not bump it, so consumers should ignore fields they do not know.
+//!
+//! Version 2 changed `Column.data_type` from the `information_schema` name
+//! (`character varying`, `ARRAY`) to the full type as written in DDL
+//! (`character varying(50)`, `integer[]`).
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::diff::engine::{DiffStats, SchemaDiff};
+
+/// Version of the JSON diff document layout
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct DiffDocument<'a> {
+    format_version: u32,
+    from: &'a str,
+    to: &'a str,
+    has_changes: bool,
+    stats: DiffStats,
+    diff: &'a SchemaDiff,
+}
+
+/// Renders a diff between two references as a pretty-printed JSON document
+pub fn render_diff(diff: &SchemaDiff, from: &str, to: &str) -> Result<String> {
+    let document = DiffDocument {
+        format_version: FORMAT_VERSION,
+        from,
+        to,
+        has_changes: diff.has_changes(),
+        stats: diff.stats(),
+        diff,
+    };
+
+    serde_json::to_string_pretty(&document).context("Failed to serialize diff")
+}
+
+/// Prints a diff between two references as JSON to stdout
+pub fn print_diff(diff: &SchemaDiff, from: &str, to: &str) -> Result<()> {
+    println!("{}", render_diff(diff, from, to)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::models::{EnumType, Schema};
+    use serde_json::Value;
+
+    #[test]
+    fn renders_document_fields() {
+        let mut new = Schema::new();
+        new.schemas.push("audit".to_string());
+        new.enums.push(EnumType {
+            name: "mood".to_string(),
+            schema: "audit".to_string(),
+            labels: vec!["happy".to_string()],
+        });
+        let diff = SchemaDiff::compare(&Schema::new(), &new);
+
+        let document: Value = serde_json::from_str(&render_diff(&diff, "latest", "current").unwrap()).unwrap();
+
+        assert_eq!(document["format_version"], 2);
+        assert_eq!(document["from"], "latest");
+        assert_eq!(document["to"], "current");
+        assert_eq!(document["has_changes"], true);
+        assert_eq!(document["stats"]["schemas_added"], 1);
+        assert_eq!(document["stats"]["types_added"], 1);
+        assert_eq!(document["stats"]["tables_added"], 0);
+        assert_eq!(document["diff"]["schemas_added"][0], "audit");
+        assert_eq!(document["diff"]["enums_added"][0]["name"], "mood");
+    }
+
+    #[test]
+    fn renders_empty_diff() {
+        let diff = SchemaDiff::compare(&Schema::new(), &Schema::new());
+
+        let document: Value = serde_json::from_str(&render_diff(&diff, "1", "2").unwrap()).unwrap();
+
+        assert_eq!(document["format_version"], FORMAT_VERSION);
+        assert_eq!(document["has_changes"], false);
+        assert_eq!(document["stats"]["schemas_added"], 0);
+    }
+}
@@ -1 +1,3 @@
+pub mod json;
+pub mod sql;
 pub mod terminal;
@@ -0,0 +1,2030 @@
+use anyhow::Result;
+
+use crate::diff::engine::{
+    diff_grants, ColumnDiff, CompositeTypeDiff, DomainDiff, EnumDiff, EventTriggerDiff, RoutineDiff,
+    SchemaDiff, SequenceDiff, TableDiff, ViewDiff,
+};
+use crate::schema::models::{MaterializedView, ParallelSafety, Routine, RoutineKind, View, Volatility};
+use crate::schema::models::{
+    Column, Constraint, ConstraintType, ForeignKeyAction, ForeignKeyMatch, Index, IndexKeyOptions, Table,
+};
+use crate::schema::models::{CompositeType, Domain, EnumType, GeneratedColumn, GeneratedKind, PartitionStrategy};
+use crate::schema::models::{EventTrigger, Identity, Sequence, SequenceOwner, Trigger, TriggerEnabled};
+use crate::schema::models::{Grant, Policy, PolicyCommand};
+
+/// PostgreSQL reserved keywords that must be quoted when used as identifiers
+const RESERVED_KEYWORDS: &[&str] = &[
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric",
+    "authorization", "binary", "both", "case", "cast", "check", "collate", "collation",
+    "column", "concurrently", "constraint", "create", "cross", "current_catalog",
+    "current_date", "current_role", "current_schema", "current_time", "current_timestamp",
+    "current_user", "default", "deferrable", "desc", "distinct", "do", "else", "end",
+    "except", "false", "fetch", "for", "foreign", "freeze", "from", "full", "grant",
+    "group", "having", "ilike", "in", "initially", "inner", "intersect", "into", "is",
+    "isnull", "join", "lateral", "leading", "left", "like", "limit", "localtime",
+    "localtimestamp", "natural", "not", "notnull", "null", "offset", "on", "only", "or",
+    "order", "outer", "overlaps", "placing", "primary", "references", "returning", "right",
+    "select", "session_user", "similar", "some", "symmetric", "system_user", "table",
+    "tablesample", "then", "to", "trailing", "true", "union", "unique", "user", "using",
+    "variadic", "verbose", "when", "where", "window", "with",
+];
+
+/// Generates ordered PostgreSQL DDL that turns the old schema of `diff` into the new one.
+///
+/// Statements are ordered so dependencies are respected: views and foreign
+/// keys are dropped before anything they reference, and tables are created
+/// before foreign keys and views that point at them. Changed views are
+/// dropped and recreated rather than replaced, since `CREATE OR REPLACE`
+/// cannot drop or retype columns.
+pub fn generate_migration(diff: &SchemaDiff) -> Result<Vec<String>> {
+    let mut statements = Vec::new();
+
+    // 1. New schemas
+    for name in &diff.schemas_added {
+        statements.push(format!("CREATE SCHEMA {};", quote_ident(name)));
+    }
+
+    // 2. Drop identity from columns that lose it, which also frees the name
+    // of its implicit sequence
+    for table_diff in &diff.tables_modified {
+        for column_diff in &table_diff.columns_modified {
+            if column_diff.old.identity.is_some() && column_diff.new.identity.is_none() {
+                statements.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} DROP IDENTITY;",
+                    qualified(&table_diff.schema_name, &table_diff.table_name),
+                    quote_ident(&column_diff.column_name)
+                ));
+            }
+        }
+    }
+
+    // 3. New and changed sequences, which column defaults may use. Ownership
+    // is attached once the owning columns exist.
+    for sequence in &diff.sequences_added {
+        statements.push(create_sequence(sequence));
+    }
+    for sequence_diff in diff.sequences_modified.iter().filter(|s| sequence_options_changed(s)) {
+        statements.push(alter_sequence(&sequence_diff.new));
+    }
+
+    // 4. New and changed types, before any column, routine or type that uses them.
+    // Enums come first, as domains and composite types may be built on them.
+    for enum_type in &diff.enums_added {
+        statements.push(create_enum(enum_type));
+    }
+    for enum_diff in &diff.enums_modified {
+        statements.extend(alter_enum(enum_diff));
+    }
+    for domain in &diff.domains_added {
+        statements.push(create_domain(domain));
+    }
+    for domain_diff in &diff.domains_modified {
+        statements.extend(alter_domain(domain_diff));
+    }
+    for composite in &diff.composite_types_added {
+        statements.push(create_composite_type(composite));
+    }
+    for composite_diff in &diff.composite_types_modified {
+        statements.extend(alter_composite_type(composite_diff));
+    }
+
+    // 5. Drop changed and removed views, which may depend on anything below
+    for view in &diff.views_removed {
+        statements.push(format!("DROP VIEW {};", qualified(&view.schema, &view.name)));
+    }
+    for view_diff in diff.views_modified.iter().filter(|v| v.needs_rebuild()) {
+        statements.push(format!(
+            "DROP VIEW {};",
+            qualified(&view_diff.schema_name, &view_diff.view_name)
+        ));
+    }
+    for view in &diff.materialized_views_removed {
+        statements.push(format!(
+            "DROP MATERIALIZED VIEW {};",
+            qualified(&view.schema, &view.name)
+        ));
+    }
+    for view_diff in &diff.materialized_views_modified {
+        if view_diff.needs_rebuild() {
+            statements.push(format!(
+                "DROP MATERIALIZED VIEW {};",
+                qualified(&view_diff.schema_name, &view_diff.view_name)
+            ));
+        } else {
+            let removed = view_diff.indexes_removed.iter();
+            let modified = view_diff.indexes_modified.iter().map(|i| &i.old);
+            for index in removed.chain(modified) {
+                statements.push(format!(
+                    "DROP INDEX {};",
+                    qualified(&view_diff.schema_name, &index.name)
+                ));
+            }
+        }
+    }
+
+    // 6. Drop removed and redefined triggers and policies, which depend on the
+    // routines they call
+    for trigger in &diff.event_triggers_removed {
+        statements.push(format!("DROP EVENT TRIGGER {};", quote_ident(&trigger.name)));
+    }
+    for trigger_diff in diff.event_triggers_modified.iter().filter(|t| event_trigger_redefined(t)) {
+        statements.push(format!("DROP EVENT TRIGGER {};", quote_ident(&trigger_diff.trigger_name)));
+    }
+    for table in diff.tables_removed.iter().chain(&diff.partitions_removed) {
+        for trigger in &table.triggers {
+            statements.push(drop_trigger(&table.schema, &table.name, trigger));
+        }
+        for policy in &table.policies {
+            statements.push(drop_policy(&table.schema, &table.name, &policy.name));
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let removed = table_diff.triggers_removed.iter();
+        let redefined = table_diff
+            .triggers_modified
+            .iter()
+            .filter(|t| t.old.definition != t.new.definition)
+            .map(|t| &t.old);
+        for trigger in removed.chain(redefined) {
+            statements.push(drop_trigger(&table_diff.schema_name, &table_diff.table_name, trigger));
+        }
+
+        let removed = table_diff.policies_removed.iter();
+        let recreated = table_diff
+            .policies_modified
+            .iter()
+            .filter(|p| p.needs_recreate())
+            .map(|p| &p.old);
+        for policy in removed.chain(recreated) {
+            statements.push(drop_policy(&table_diff.schema_name, &table_diff.table_name, &policy.name));
+        }
+    }
+
+    // 7. Drop removed routines, and changed ones that CREATE OR REPLACE cannot update
+    for routine in &diff.routines_removed {
+        statements.push(drop_routine(routine));
+    }
+    for routine_diff in diff.routines_modified.iter().filter(|r| needs_drop(r)) {
+        statements.push(drop_routine(&routine_diff.old));
+    }
+
+    // 8. Drop foreign keys, so nothing they reference is still in use
+    for table in diff.tables_removed.iter().chain(&diff.partitions_removed) {
+        for constraint in foreign_keys(&table.constraints) {
+            statements.push(drop_constraint(&table.schema, &table.name, &constraint.name));
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let removed = table_diff.constraints_removed.iter();
+        let modified = table_diff
+            .constraints_modified
+            .iter()
+            .filter(|c| !c.is_validation_only())
+            .map(|c| &c.old);
+        for constraint in removed.chain(modified).filter(|c| is_foreign_key(c)) {
+            statements.push(drop_constraint(&table_diff.schema_name, &table_diff.table_name, &constraint.name));
+        }
+    }
+
+    // 9. Drop remaining constraints and indexes of modified tables
+    for table_diff in &diff.tables_modified {
+        let removed = table_diff.constraints_removed.iter();
+        let modified = table_diff
+            .constraints_modified
+            .iter()
+            .filter(|c| !c.is_validation_only())
+            .map(|c| &c.old);
+        for constraint in removed.chain(modified).filter(|c| !is_foreign_key(c) && !is_not_null(c)) {
+            statements.push(drop_constraint(&table_diff.schema_name, &table_diff.table_name, &constraint.name));
+        }
+
+        let removed = table_diff.indexes_removed.iter();
+        let modified = table_diff.indexes_modified.iter().map(|i| &i.old);
+        for index in removed.chain(modified) {
+            if constraint_changed(index, table_diff, Side::Old) {
+                continue;
+            }
+            if backs_constraint(index) {
+                statements.push(format!(
+                    "-- WARNING: cannot change index {} of {} on its own; it belongs to the constraint of the same name",
+                    quote_ident(&index.name),
+                    qualified(&table_diff.schema_name, &table_diff.table_name)
+                ));
+            } else {
+                statements.push(format!(
+                    "DROP INDEX {};",
+                    qualified(&table_diff.schema_name, &index.name)
+                ));
+            }
+        }
+    }
+
+    // 10. Detach partitions that move to another parent or bound, then drop
+    // removed partitions (unless their parent is dropped too) and tables
+    for table_diff in diff.tables_modified.iter().filter(|t| t.partition_bound_changed()) {
+        if let Some(partition_of) = &table_diff.old_partition_of {
+            statements.push(format!(
+                "ALTER TABLE {} DETACH PARTITION {};",
+                qualified(&partition_of.parent_schema, &partition_of.parent_table),
+                qualified(&table_diff.schema_name, &table_diff.table_name)
+            ));
+        }
+    }
+    for partition in diff.partitions_removed.iter().filter(|p| !parent_removed(diff, p)) {
+        let partition = qualified(&partition.schema, &partition.name);
+        statements.push(format!("-- WARNING: irreversible, drops partition {} and all of its data", partition));
+        statements.push(format!("DROP TABLE {};", partition));
+    }
+    for table in &diff.tables_removed {
+        let table = qualified(&table.schema, &table.name);
+        statements.push(format!("-- WARNING: irreversible, drops table {} and all of its data", table));
+        statements.push(format!("DROP TABLE {};", table));
+    }
+
+    // 11. Create new and changed routines before any table or column that may use them.
+    // Bodies are not validated, as they may reference tables created below.
+    if !diff.routines_added.is_empty() || diff.routines_modified.iter().any(routine_redefined) {
+        statements.push("SET check_function_bodies = false;".to_string());
+    }
+    for routine in &diff.routines_added {
+        statements.push(create_routine(routine));
+    }
+    for routine_diff in diff.routines_modified.iter().filter(|r| routine_redefined(r)) {
+        statements.push(create_routine(&routine_diff.new));
+    }
+
+    // 12. Column changes on modified tables
+    for table_diff in diff.tables_modified.iter().filter(|t| t.partitioning_changed()) {
+        statements.push(format!(
+            "-- WARNING: cannot change how {} is partitioned in place; recreate it and move its rows",
+            qualified(&table_diff.schema_name, &table_diff.table_name)
+        ));
+    }
+    for table_diff in &diff.tables_modified {
+        statements.extend(column_changes(table_diff));
+    }
+
+    // 13. Create new tables with their key constraints inline, then new
+    // partitions under them, and attach partitions detached in step 10
+    for table in &diff.tables_added {
+        statements.push(create_table(table));
+    }
+    for partition in partitions_in_creation_order(&diff.partitions_added) {
+        statements.push(create_partition(partition)?);
+    }
+    for table_diff in diff.tables_modified.iter().filter(|t| t.partition_bound_changed()) {
+        if let Some(partition_of) = &table_diff.new_partition_of {
+            statements.push(format!(
+                "ALTER TABLE {} ATTACH PARTITION {} {};",
+                qualified(&partition_of.parent_schema, &partition_of.parent_table),
+                qualified(&table_diff.schema_name, &table_diff.table_name),
+                partition_of.bound
+            ));
+        }
+    }
+
+    // 14. Attach sequences to their owning columns, and drop removed sequences
+    // that were not already dropped along with their owning column
+    for sequence in diff.sequences_added.iter().filter(|s| s.owned_by.is_some()) {
+        statements.push(sequence_owned_by(sequence));
+    }
+    for sequence_diff in diff.sequences_modified.iter().filter(|s| s.old.owned_by != s.new.owned_by) {
+        statements.push(sequence_owned_by(&sequence_diff.new));
+    }
+    for sequence in &diff.sequences_removed {
+        if !sequence.owned_by.as_ref().is_some_and(|owner| owner_removed(diff, owner)) {
+            statements.push(format!(
+                "DROP SEQUENCE {};",
+                qualified(&sequence.schema, &sequence.name)
+            ));
+        }
+    }
+
+    // 15. Create indexes and non-foreign-key constraints. Partitions are
+    // created without a column list, so their key constraints are added here too.
+    for table in diff.tables_added.iter().chain(&diff.partitions_added) {
+        let constraints = table.constraints.iter().filter(|c| table.is_partition() || !is_inline(c));
+        for constraint in constraints.filter(|c| !is_foreign_key(c) && !is_not_null(c)) {
+            statements.push(add_constraint(&table.schema, &table.name, constraint));
+        }
+        for index in &table.indexes {
+            if !table.constraints.iter().any(|c| c.name == index.name) {
+                statements.push(create_index(&table.schema, &table.name, index));
+            }
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let added = table_diff.constraints_added.iter();
+        let modified = table_diff
+            .constraints_modified
+            .iter()
+            .filter(|c| !c.is_validation_only())
+            .map(|c| &c.new);
+        for constraint in added.chain(modified).filter(|c| !is_foreign_key(c) && !is_not_null(c)) {
+            statements.push(add_constraint(&table_diff.schema_name, &table_diff.table_name, constraint));
+        }
+
+        let added = table_diff.indexes_added.iter();
+        let modified = table_diff.indexes_modified.iter().map(|i| &i.new);
+        for index in added.chain(modified) {
+            if !backs_constraint(index) && !constraint_changed(index, table_diff, Side::New) {
+                statements.push(create_index(&table_diff.schema_name, &table_diff.table_name, index));
+            }
+        }
+    }
+
+    // 16. Foreign keys, once every referenced table exists
+    for table in diff.tables_added.iter().chain(&diff.partitions_added) {
+        for constraint in foreign_keys(&table.constraints) {
+            statements.push(add_constraint(&table.schema, &table.name, constraint));
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let added = table_diff.constraints_added.iter();
+        let modified = table_diff
+            .constraints_modified
+            .iter()
+            .filter(|c| !c.is_validation_only())
+            .map(|c| &c.new);
+        for constraint in added.chain(modified).filter(|c| is_foreign_key(c)) {
+            statements.push(add_constraint(&table_diff.schema_name, &table_diff.table_name, constraint));
+        }
+
+        // Constraints that were only marked NOT VALID are validated in place
+        for constraint_diff in table_diff.constraints_modified.iter().filter(|c| c.is_validation_only()) {
+            statements.push(format!(
+                "ALTER TABLE {} VALIDATE CONSTRAINT {};",
+                qualified(&table_diff.schema_name, &table_diff.table_name),
+                quote_ident(&constraint_diff.constraint_name)
+            ));
+        }
+    }
+
+    // 17. Create new and changed views last, once everything they select from exists
+    for view in &diff.views_added {
+        statements.push(create_view(view));
+    }
+    for view_diff in diff.views_modified.iter().filter(|v| v.needs_rebuild()) {
+        statements.push(format!(
+            "CREATE VIEW {} AS\n{};",
+            qualified(&view_diff.schema_name, &view_diff.view_name),
+            view_diff.new_definition
+        ));
+    }
+    for view in &diff.materialized_views_added {
+        statements.extend(create_materialized_view(view));
+    }
+    for view_diff in &diff.materialized_views_modified {
+        if view_diff.needs_rebuild() {
+            statements.push(format!(
+                "CREATE MATERIALIZED VIEW {} AS\n{};",
+                qualified(&view_diff.schema_name, &view_diff.view_name),
+                view_diff.new_definition
+            ));
+            // Dropping the view dropped all of its indexes, so recreate every one
+            for index in &view_diff.new_indexes {
+                statements.push(create_index(&view_diff.schema_name, &view_diff.view_name, index));
+            }
+        } else {
+            let added = view_diff.indexes_added.iter();
+            let modified = view_diff.indexes_modified.iter().map(|i| &i.new);
+            for index in added.chain(modified) {
+                statements.push(create_index(&view_diff.schema_name, &view_diff.view_name, index));
+            }
+        }
+    }
+
+    // 18. Create new and redefined triggers and policies, and apply enabled
+    // state changes. Row-level security is switched once the policies exist.
+    for table in diff.tables_added.iter().chain(&diff.partitions_added) {
+        for trigger in &table.triggers {
+            statements.extend(create_trigger(&table.schema, &table.name, trigger));
+        }
+        for policy in &table.policies {
+            statements.push(create_policy(&table.schema, &table.name, policy));
+        }
+        if table.rls_enabled {
+            statements.push(alter_rls(&table.schema, &table.name, "ENABLE ROW LEVEL SECURITY"));
+        }
+        if table.rls_forced {
+            statements.push(alter_rls(&table.schema, &table.name, "FORCE ROW LEVEL SECURITY"));
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let (schema, table) = (&table_diff.schema_name, &table_diff.table_name);
+        for trigger in &table_diff.triggers_added {
+            statements.extend(create_trigger(schema, table, trigger));
+        }
+        for trigger_diff in &table_diff.triggers_modified {
+            if trigger_diff.old.definition != trigger_diff.new.definition {
+                statements.extend(create_trigger(schema, table, &trigger_diff.new));
+            } else if trigger_diff.old.enabled != trigger_diff.new.enabled {
+                statements.push(alter_trigger_enabled(schema, table, &trigger_diff.new));
+            }
+        }
+        for policy in &table_diff.policies_added {
+            statements.push(create_policy(schema, table, policy));
+        }
+        for policy_diff in &table_diff.policies_modified {
+            if policy_diff.needs_recreate() {
+                statements.push(create_policy(schema, table, &policy_diff.new));
+            } else {
+                statements.push(alter_policy(schema, table, &policy_diff.new));
+            }
+        }
+        if table_diff.old_rls_enabled != table_diff.new_rls_enabled {
+            let action = if table_diff.new_rls_enabled { "ENABLE" } else { "DISABLE" };
+            statements.push(alter_rls(schema, table, &format!("{} ROW LEVEL SECURITY", action)));
+        }
+        if table_diff.old_rls_forced != table_diff.new_rls_forced {
+            let action = if table_diff.new_rls_forced { "FORCE" } else { "NO FORCE" };
+            statements.push(alter_rls(schema, table, &format!("{} ROW LEVEL SECURITY", action)));
+        }
+    }
+    for trigger in &diff.event_triggers_added {
+        statements.extend(create_event_trigger(trigger));
+    }
+    for trigger_diff in &diff.event_triggers_modified {
+        if event_trigger_redefined(trigger_diff) {
+            statements.extend(create_event_trigger(&trigger_diff.new));
+        } else {
+            statements.push(alter_event_trigger_enabled(&trigger_diff.new));
+        }
+    }
+
+    // 19. Owners and privileges, once every object exists. Removed privileges
+    // are revoked before added ones are granted, so a grant that only gained
+    // or lost WITH GRANT OPTION ends up right.
+    for privileges in &diff.schema_privileges_added {
+        let schema = format!("SCHEMA {}", quote_ident(&privileges.name));
+        statements.push(alter_owner(&schema, &privileges.owner));
+        statements.extend(grant_changes(&schema, None, &privileges.grants, &[]));
+    }
+    for privileges_diff in &diff.schema_privileges_modified {
+        let schema = format!("SCHEMA {}", quote_ident(&privileges_diff.schema_name));
+        if privileges_diff.owner_changed() {
+            statements.push(alter_owner(&schema, &privileges_diff.new.owner));
+        }
+        let (added, removed) = diff_grants(&privileges_diff.old.grants, &privileges_diff.new.grants);
+        statements.extend(grant_changes(&schema, None, &added, &removed));
+    }
+    for table in diff.tables_added.iter().chain(&diff.partitions_added) {
+        let object = format!("TABLE {}", qualified(&table.schema, &table.name));
+        if let Some(owner) = &table.owner {
+            statements.push(alter_owner(&object, owner));
+        }
+        statements.extend(grant_changes(&object, None, &table.grants, &[]));
+        for column in &table.columns {
+            statements.extend(grant_changes(&object, Some(&column.name), &column.grants, &[]));
+        }
+    }
+    for table_diff in &diff.tables_modified {
+        let object = format!("TABLE {}", qualified(&table_diff.schema_name, &table_diff.table_name));
+        if let (true, Some(owner)) = (table_diff.owner_changed(), &table_diff.new_owner) {
+            statements.push(alter_owner(&object, owner));
+        }
+        statements.extend(grant_changes(&object, None, &table_diff.grants_added, &table_diff.grants_removed));
+        for column in &table_diff.columns_added {
+            statements.extend(grant_changes(&object, Some(&column.name), &column.grants, &[]));
+        }
+        for column_diff in &table_diff.columns_modified {
+            // A recreated column starts out without grants
+            let old_grants: &[Grant] = if column_recreated(column_diff) { &[] } else { &column_diff.old.grants };
+            let (added, removed) = diff_grants(old_grants, &column_diff.new.grants);
+            statements.extend(grant_changes(&object, Some(&column_diff.column_name), &added, &removed));
+        }
+    }
+    for view in &diff.views_added {
+        statements.extend(created_view_privileges(
+            "VIEW",
+            &view.schema,
+            &view.name,
+            &view.owner,
+            &view.grants,
+            &view.columns,
+        ));
+    }
+    for view in &diff.materialized_views_added {
+        statements.extend(created_view_privileges(
+            "MATERIALIZED VIEW",
+            &view.schema,
+            &view.name,
+            &view.owner,
+            &view.grants,
+            &view.columns,
+        ));
+    }
+    let views = diff.views_modified.iter().map(|v| ("VIEW", v));
+    let materialized_views = diff.materialized_views_modified.iter().map(|v| ("MATERIALIZED VIEW", v));
+    for (keyword, view_diff) in views.chain(materialized_views) {
+        statements.extend(view_privilege_changes(keyword, view_diff));
+    }
+    for sequence in &diff.sequences_added {
+        let object = format!("SEQUENCE {}", qualified(&sequence.schema, &sequence.name));
+        if let Some(owner) = &sequence.owner {
+            statements.push(alter_owner(&object, owner));
+        }
+        statements.extend(grant_changes(&object, None, &sequence.grants, &[]));
+    }
+    for sequence_diff in &diff.sequences_modified {
+        let (old, new) = (&sequence_diff.old, &sequence_diff.new);
+        let object = format!("SEQUENCE {}", qualified(&new.schema, &new.name));
+        if let (true, Some(owner)) = (sequence_diff.owner_changed(), &new.owner) {
+            statements.push(alter_owner(&object, owner));
+        }
+        let (added, removed) = diff_grants(&old.grants, &new.grants);
+        statements.extend(grant_changes(&object, None, &added, &removed));
+    }
+    for routine in &diff.routines_added {
+        statements.extend(created_routine_privileges(routine));
+    }
+    for routine_diff in &diff.routines_modified {
+        if needs_drop(routine_diff) {
+            statements.extend(created_routine_privileges(&routine_diff.new));
+            continue;
+        }
+        let (old, new) = (&routine_diff.old, &routine_diff.new);
+        let object = routine_object(new);
+        if let (true, Some(owner)) = (routine_diff.owner_changed(), &new.owner) {
+            statements.push(alter_owner(&object, owner));
+        }
+        let (added, removed) = diff_grants(&old.grants, &new.grants);
+        statements.extend(grant_changes(&object, None, &added, &removed));
+    }
+
+    // 20. Drop removed types, now that no column or routine uses them
+    for composite in &diff.composite_types_removed {
+        statements.push(format!("DROP TYPE {};", qualified(&composite.schema, &composite.name)));
+    }
+    for domain in &diff.domains_removed {
+        statements.push(format!("DROP DOMAIN {};", qualified(&domain.schema, &domain.name)));
+    }
+    for enum_type in &diff.enums_removed {
+        statements.push(format!("DROP TYPE {};", qualified(&enum_type.schema, &enum_type.name)));
+    }
+
+    // 21. Drop schemas that are gone, now that nothing inside them remains
+    for name in &diff.schemas_removed {
+        statements.push(format!("DROP SCHEMA {};", quote_ident(name)));
+    }
+
+    Ok(statements)
+}
+
+/// Prints the migration for a diff as a SQL script
+pub fn print_migration(diff: &SchemaDiff) -> Result<()> {
+    print_statements(generate_migration(diff)?);
+    Ok(())
+}
+
+/// Generates the DDL that reverts `diff`, taking its new schema back to the old one
+pub fn generate_rollback(diff: &SchemaDiff) -> Result<Vec<String>> {
+    generate_migration(&diff.invert())
+}
+
+/// Prints the rollback for a diff as a SQL script
+pub fn print_rollback(diff: &SchemaDiff) -> Result<()> {
+    print_statements(generate_rollback(diff)?);
+    Ok(())
+}
+
+fn print_statements(statements: Vec<String>) {
+    if statements.is_empty() {
+        println!("-- No changes detected");
+        return;
+    }
+
+    for statement in statements {
+        println!("{}", statement);
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Old,
+    New,
+}
+
+/// Whether an index is the implicit index of a PK/unique/exclusion constraint,
+/// which is dropped and created with the constraint rather than on its own
+fn backs_constraint(index: &Index) -> bool {
+    index.is_primary || index.backs_constraint
+}
+
+/// Whether a same-named constraint, and with it the index, is being dropped
+/// (old side) or created (new side) in this diff
+fn constraint_changed(index: &Index, table_diff: &TableDiff, side: Side) -> bool {
+    match side {
+        Side::Old => table_diff
+            .constraints_removed
+            .iter()
+            .chain(table_diff.constraints_modified.iter().map(|c| &c.old))
+            .any(|c| c.name == index.name),
+        Side::New => table_diff
+            .constraints_added
+            .iter()
+            .chain(table_diff.constraints_modified.iter().map(|c| &c.new))
+            .any(|c| c.name == index.name),
+    }
+}
+
+fn column_changes(table_diff: &TableDiff) -> Vec<String> {
+    let table = qualified(&table_diff.schema_name, &table_diff.table_name);
+    let mut statements = Vec::new();
+
+    for column in &table_diff.columns_added {
+        statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table, column_definition(column)));
+    }
+
+    for column_diff in &table_diff.columns_modified {
+        statements.extend(alter_column(&table, column_diff));
+    }
+
+    for column in &table_diff.columns_removed {
+        let column = quote_ident(&column.name);
+        statements.push(format!(
+            "-- WARNING: irreversible, drops column {} of {} and its data",
+            column, table
+        ));
+        statements.push(format!("ALTER TABLE {} DROP COLUMN {};", table, column));
+    }
+
+    statements
+}
+
+fn alter_column(table: &str, diff: &ColumnDiff) -> Vec<String> {
+    let column = quote_ident(&diff.column_name);
+    let mut statements = Vec::new();
+
+    // A regular column cannot be made generated, nor a stored one virtual, in
+    // place, so it is recreated and everything else comes from the new definition
+    if column_recreated(diff) {
+        statements.push(format!(
+            "-- WARNING: irreversible, recreates {} of {} as a generated column, dropping its data and anything that depends on it",
+            column, table
+        ));
+        statements.push(format!("ALTER TABLE {} DROP COLUMN {};", table, column));
+        statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table, column_definition(&diff.new)));
+        return statements;
+    }
+
+    match (&diff.old.generated, &diff.new.generated) {
+        (Some(_), None) => {
+            statements.push(format!("ALTER TABLE {} ALTER COLUMN {} DROP EXPRESSION;", table, column));
+        }
+        (Some(old), Some(new)) if old.expression != new.expression => {
+            statements.push("-- WARNING: SET EXPRESSION requires PostgreSQL 17 or later".to_string());
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} SET EXPRESSION AS ({});",
+                table, column, new.expression
+            ));
+        }
+        _ => {}
+    }
+
+    if diff.lossy_type_change() {
+        statements.push(format!(
+            "-- WARNING: converting {} from {} to {} may fail or lose data",
+            column, diff.old.data_type, diff.new.data_type
+        ));
+    }
+
+    // Collation can only be changed together with the type
+    if diff.type_change.is_some() || diff.collation_changed() {
+        statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} TYPE {}{};",
+            table,
+            column,
+            diff.new.data_type,
+            collate_clause(&diff.new)
+        ));
+    }
+
+    if diff.old.default_value != diff.new.default_value {
+        statements.push(match &diff.new.default_value {
+            Some(default) => format!("ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};", table, column, default),
+            None => format!("ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;", table, column),
+        });
+    }
+
+    if diff.old.is_nullable != diff.new.is_nullable {
+        let action = if diff.new.is_nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+        statements.push(format!("ALTER TABLE {} ALTER COLUMN {} {};", table, column, action));
+    }
+
+    // Identity is added last, as it requires NOT NULL and no default
+    if let Some(identity) = &diff.new.identity {
+        match &diff.old.identity {
+            None => statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} ADD {};",
+                table,
+                column,
+                identity_clause(identity)
+            )),
+            Some(old) if old != identity => statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} SET {};",
+                table,
+                column,
+                generated_clause(identity)
+            )),
+            Some(_) => {}
+        }
+    }
+
+    statements
+}
+
+/// Whether `alter_column` drops and re-adds the column rather than altering it
+fn column_recreated(diff: &ColumnDiff) -> bool {
+    let generated_kind = |column: &Column| column.generated.as_ref().map(|g| g.kind.clone());
+    diff.new.generated.is_some() && generated_kind(&diff.old) != generated_kind(&diff.new)
+}
+
+fn identity_clause(identity: &Identity) -> String {
+    format!("{} AS IDENTITY", generated_clause(identity))
+}
+
+fn generated_clause(identity: &Identity) -> &'static str {
+    match identity {
+        Identity::Always => "GENERATED ALWAYS",
+        Identity::ByDefault => "GENERATED BY DEFAULT",
+    }
+}
+
+fn create_table(table: &Table) -> String {
+    let mut lines: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| format!("    {}", column_definition(column)))
+        .collect();
+
+    for constraint in table.constraints.iter().filter(|c| is_inline(c)) {
+        if let Some(definition) = constraint_definition(&table.schema, constraint) {
+            lines.push(format!("    CONSTRAINT {} {}", quote_ident(&constraint.name), definition));
+        }
+    }
+
+    format!(
+        "CREATE TABLE {} (\n{}\n){};",
+        qualified(&table.schema, &table.name),
+        lines.join(",\n"),
+        partition_by(table)
+    )
+}
+
+/// `CREATE TABLE ... PARTITION OF`; columns come from the parent
+fn create_partition(partition: &Table) -> Result<String> {
+    let Some(partition_of) = &partition.partition_of else {
+        anyhow::bail!("Partition {} has no parent table", partition.qualified_name());
+    };
+    Ok(format!(
+        "CREATE TABLE {} PARTITION OF {} {}{};",
+        qualified(&partition.schema, &partition.name),
+        qualified(&partition_of.parent_schema, &partition_of.parent_table),
+        partition_of.bound,
+        partition_by(partition)
+    ))
+}
+
+/// ` PARTITION BY ...` clause of a partitioned table, or nothing
+fn partition_by(table: &Table) -> String {
+    match &table.partitioning {
+        Some(partitioning) => {
+            let strategy = match partitioning.strategy {
+                PartitionStrategy::Range => "RANGE",
+                PartitionStrategy::List => "LIST",
+                PartitionStrategy::Hash => "HASH",
+            };
+            format!(" PARTITION BY {} ({})", strategy, partitioning.key)
+        }
+        None => String::new(),
+    }
+}
+
+/// Added partitions, with partitions that are themselves partitioned ahead
+/// of their own partitions
+fn partitions_in_creation_order(partitions: &[Table]) -> Vec<&Table> {
+    let mut ordered = Vec::new();
+    let mut pending: Vec<&Table> = partitions.iter().collect();
+
+    while !pending.is_empty() {
+        let (waiting, ready): (Vec<&Table>, Vec<&Table>) = pending.iter().partition(|p| {
+            let parent = p.partition_of.as_ref();
+            pending.iter().any(|other| {
+                parent.is_some_and(|parent| parent.parent_schema == other.schema && parent.parent_table == other.name)
+            })
+        });
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            break;
+        }
+        ordered.extend(ready);
+        pending = waiting;
+    }
+
+    ordered
+}
+
+/// Whether the parent of a removed partition is dropped by this diff, taking the partition with it
+fn parent_removed(diff: &SchemaDiff, partition: &Table) -> bool {
+    let Some(partition_of) = &partition.partition_of else {
+        return false;
+    };
+    diff.tables_removed
+        .iter()
+        .chain(&diff.partitions_removed)
+        .any(|t| t.schema == partition_of.parent_schema && t.name == partition_of.parent_table)
+}
+
+fn create_view(view: &View) -> String {
+    format!(
+        "CREATE VIEW {} AS\n{};",
+        qualified(&view.schema, &view.name),
+        view.definition
+    )
+}
+
+fn create_materialized_view(view: &MaterializedView) -> Vec<String> {
+    let mut statements = vec![format!(
+        "CREATE MATERIALIZED VIEW {} AS\n{};",
+        qualified(&view.schema, &view.name),
+        view.definition
+    )];
+
+    for index in &view.indexes {
+        statements.push(create_index(&view.schema, &view.name, index));
+    }
+
+    statements
+}
+
+fn create_sequence(sequence: &Sequence) -> String {
+    format!(
+        "CREATE SEQUENCE {} {};",
+        qualified(&sequence.schema, &sequence.name),
+        sequence_options(sequence)
+    )
+}
+
+fn alter_sequence(sequence: &Sequence) -> String {
+    format!(
+        "ALTER SEQUENCE {} {};",
+        qualified(&sequence.schema, &sequence.name),
+        sequence_options(sequence)
+    )
+}
+
+/// Every option is spelled out, so changing the type and its bounds together applies cleanly
+fn sequence_options(sequence: &Sequence) -> String {
+    format!(
+        "AS {} INCREMENT BY {} MINVALUE {} MAXVALUE {} START WITH {} {}",
+        sequence.data_type,
+        sequence.increment,
+        sequence.min_value,
+        sequence.max_value,
+        sequence.start_value,
+        if sequence.cycle { "CYCLE" } else { "NO CYCLE" }
+    )
+}
+
+fn sequence_options_changed(diff: &SequenceDiff) -> bool {
+    let (old, new) = (&diff.old, &diff.new);
+    old.data_type != new.data_type
+        || old.start_value != new.start_value
+        || old.increment != new.increment
+        || old.min_value != new.min_value
+        || old.max_value != new.max_value
+        || old.cycle != new.cycle
+}
+
+fn sequence_owned_by(sequence: &Sequence) -> String {
+    let owner = match &sequence.owned_by {
+        Some(owner) => format!(
+            "{}.{}",
+            qualified(&owner.schema, &owner.table),
+            quote_ident(&owner.column)
+        ),
+        None => "NONE".to_string(),
+    };
+    format!(
+        "ALTER SEQUENCE {} OWNED BY {};",
+        qualified(&sequence.schema, &sequence.name),
+        owner
+    )
+}
+
+/// Whether the column owning a sequence is dropped by this diff, taking the sequence with it
+fn owner_removed(diff: &SchemaDiff, owner: &SequenceOwner) -> bool {
+    let table_removed = diff
+        .tables_removed
+        .iter()
+        .chain(&diff.partitions_removed)
+        .any(|t| t.schema == owner.schema && t.name == owner.table);
+    let column_removed = diff.tables_modified.iter().any(|t| {
+        t.schema_name == owner.schema
+            && t.table_name == owner.table
+            && t.columns_removed.iter().any(|c| c.name == owner.column)
+    });
+    table_removed || column_removed
+}
+
+fn create_enum(enum_type: &EnumType) -> String {
+    let labels: Vec<String> = enum_type.labels.iter().map(|l| quote_literal(l)).collect();
+    format!(
+        "CREATE TYPE {} AS ENUM ({});",
+        qualified(&enum_type.schema, &enum_type.name),
+        labels.join(", ")
+    )
+}
+
+/// Adds new labels in place. Removing or reordering labels needs the type to
+/// be recreated and every column using it converted, which is left to the user.
+fn alter_enum(diff: &EnumDiff) -> Vec<String> {
+    let name = qualified(&diff.new.schema, &diff.new.name);
+
+    if !diff.is_additive() {
+        return vec![format!(
+            "-- WARNING: cannot remove or reorder labels of enum {} in place; recreate it and convert the columns that use it",
+            name
+        )];
+    }
+
+    let labels = &diff.new.labels;
+    labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| diff.labels_added.contains(label))
+        .map(|(i, label)| {
+            // Labels are added in order, so the previous one always exists by now
+            let position = if i > 0 {
+                format!(" AFTER {}", quote_literal(&labels[i - 1]))
+            } else if labels.len() > 1 {
+                format!(" BEFORE {}", quote_literal(&labels[1]))
+            } else {
+                String::new()
+            };
+            format!("ALTER TYPE {} ADD VALUE {}{};", name, quote_literal(label), position)
+        })
+        .collect()
+}
+
+fn create_domain(domain: &Domain) -> String {
+    let mut definition = format!(
+        "CREATE DOMAIN {} AS {}",
+        qualified(&domain.schema, &domain.name),
+        domain.base_type
+    );
+
+    if let Some(default) = &domain.default_value {
+        definition.push_str(&format!(" DEFAULT {}", default));
+    }
+
+    if domain.not_null {
+        definition.push_str(" NOT NULL");
+    }
+
+    for constraint in &domain.constraints {
+        definition.push_str(&format!(
+            "\n    CONSTRAINT {} {}",
+            quote_ident(&constraint.name),
+            constraint.definition
+        ));
+    }
+
+    definition.push(';');
+    definition
+}
+
+fn alter_domain(diff: &DomainDiff) -> Vec<String> {
+    let (old, new) = (&diff.old, &diff.new);
+    let name = qualified(&new.schema, &new.name);
+    let mut statements = Vec::new();
+
+    if old.base_type != new.base_type {
+        statements.push(format!(
+            "-- WARNING: cannot change the base type of domain {} from {} to {} in place",
+            name, old.base_type, new.base_type
+        ));
+    }
+
+    // Constraints whose definition changed are dropped and added again
+    for constraint in old.constraints.iter().filter(|c| !new.constraints.contains(c)) {
+        statements.push(format!(
+            "ALTER DOMAIN {} DROP CONSTRAINT {};",
+            name,
+            quote_ident(&constraint.name)
+        ));
+    }
+
+    if old.default_value != new.default_value {
+        statements.push(match &new.default_value {
+            Some(default) => format!("ALTER DOMAIN {} SET DEFAULT {};", name, default),
+            None => format!("ALTER DOMAIN {} DROP DEFAULT;", name),
+        });
+    }
+
+    if old.not_null != new.not_null {
+        let action = if new.not_null { "SET NOT NULL" } else { "DROP NOT NULL" };
+        statements.push(format!("ALTER DOMAIN {} {};", name, action));
+    }
+
+    for constraint in new.constraints.iter().filter(|c| !old.constraints.contains(c)) {
+        statements.push(format!(
+            "ALTER DOMAIN {} ADD CONSTRAINT {} {};",
+            name,
+            quote_ident(&constraint.name),
+            constraint.definition
+        ));
+    }
+
+    statements
+}
+
+fn create_composite_type(composite: &CompositeType) -> String {
+    let attributes: Vec<String> = composite
+        .attributes
+        .iter()
+        .map(|a| format!("    {} {}", quote_ident(&a.name), a.data_type))
+        .collect();
+    format!(
+        "CREATE TYPE {} AS (\n{}\n);",
+        qualified(&composite.schema, &composite.name),
+        attributes.join(",\n")
+    )
+}
+
+fn alter_composite_type(diff: &CompositeTypeDiff) -> Vec<String> {
+    let (old, new) = (&diff.old, &diff.new);
+    let name = qualified(&new.schema, &new.name);
+    let mut statements = Vec::new();
+
+    for attribute in &old.attributes {
+        if !new.attributes.iter().any(|a| a.name == attribute.name) {
+            statements.push(format!(
+                "ALTER TYPE {} DROP ATTRIBUTE {};",
+                name,
+                quote_ident(&attribute.name)
+            ));
+        }
+    }
+
+    for attribute in &new.attributes {
+        match old.attributes.iter().find(|a| a.name == attribute.name) {
+            None => statements.push(format!(
+                "ALTER TYPE {} ADD ATTRIBUTE {} {};",
+                name,
+                quote_ident(&attribute.name),
+                attribute.data_type
+            )),
+            Some(old_attribute) if old_attribute.data_type != attribute.data_type => {
+                statements.push(format!(
+                    "ALTER TYPE {} ALTER ATTRIBUTE {} TYPE {};",
+                    name,
+                    quote_ident(&attribute.name),
+                    attribute.data_type
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+
+    statements
+}
+
+/// CREATE OR REPLACE cannot change a routine's kind, return type or argument names
+fn needs_drop(diff: &RoutineDiff) -> bool {
+    diff.old.kind != diff.new.kind
+        || diff.old.return_type != diff.new.return_type
+        || diff.old.arguments != diff.new.arguments
+}
+
+/// Whether anything besides owner and grants changed, which `CREATE OR REPLACE` leaves alone
+fn routine_redefined(diff: &RoutineDiff) -> bool {
+    let new = Routine {
+        owner: diff.old.owner.clone(),
+        grants: diff.old.grants.clone(),
+        ..diff.new.clone()
+    };
+    diff.old != new
+}
+
+fn drop_routine(routine: &Routine) -> String {
+    format!(
+        "DROP {} {}({});",
+        routine_keyword(routine),
+        qualified(&routine.schema, &routine.name),
+        routine.argument_types
+    )
+}
+
+/// The captured definition as is when there is one, since `CREATE OR REPLACE`
+/// resets anything it leaves out. Older snapshots only have the attributes
+/// that were recorded then.
+fn create_routine(routine: &Routine) -> String {
+    if let Some(definition) = &routine.definition {
+        return format!("{};", definition);
+    }
+
+    if routine.language == "c" || routine.language == "internal" {
+        return format!(
+            "-- WARNING: cannot recreate {} {}: {} routines are defined outside SQL",
+            routine_keyword(routine).to_lowercase(),
+            routine.signature(),
+            routine.language
+        );
+    }
+    if routine.body.is_empty() {
+        return format!(
+            "-- WARNING: cannot recreate {} {}: its body was not captured",
+            routine_keyword(routine).to_lowercase(),
+            routine.signature()
+        );
+    }
+
+    let mut lines = vec![format!(
+        "CREATE OR REPLACE {} {}({})",
+        routine_keyword(routine),
+        qualified(&routine.schema, &routine.name),
+        routine.arguments
+    )];
+
+    if let Some(return_type) = &routine.return_type {
+        lines.push(format!("RETURNS {}", return_type));
+    }
+
+    lines.push(format!("LANGUAGE {}", routine.language));
+
+    if routine.kind == RoutineKind::Function {
+        lines.push(
+            match routine.volatility {
+                Volatility::Immutable => "IMMUTABLE",
+                Volatility::Stable => "STABLE",
+                Volatility::Volatile => "VOLATILE",
+            }
+            .to_string(),
+        );
+    }
+
+    if routine.strict {
+        lines.push("STRICT".to_string());
+    }
+    if routine.security_definer {
+        lines.push("SECURITY DEFINER".to_string());
+    }
+    if routine.leakproof {
+        lines.push("LEAKPROOF".to_string());
+    }
+    if let Some(parallel) = &routine.parallel {
+        lines.push(format!("PARALLEL {}", parallel_keyword(parallel)));
+    }
+    if let Some(cost) = routine.cost {
+        lines.push(format!("COST {}", cost));
+    }
+    if let Some(rows) = routine.rows {
+        lines.push(format!("ROWS {}", rows));
+    }
+    for setting in &routine.config {
+        lines.push(set_clause(setting));
+    }
+
+    // SQL-standard bodies are parsed, not quoted source
+    let sql_standard = routine.body.starts_with("BEGIN ATOMIC") || routine.body.starts_with("RETURN ");
+    if routine.language == "sql" && sql_standard {
+        lines.push(format!("{};", routine.body));
+    } else {
+        let tag = dollar_quote_tag(&routine.body);
+        lines.push(format!("AS {}\n{}\n{};", tag, routine.body, tag));
+    }
+
+    lines.join("\n")
+}
+
+fn parallel_keyword(parallel: &ParallelSafety) -> &'static str {
+    match parallel {
+        ParallelSafety::Safe => "SAFE",
+        ParallelSafety::Restricted => "RESTRICTED",
+        ParallelSafety::Unsafe => "UNSAFE",
+    }
+}
+
+/// `SET` clause for a `name=value` routine setting. List settings such as
+/// `search_path` hold already-quoted identifiers, so they are not quoted again.
+fn set_clause(setting: &str) -> String {
+    let (name, value) = setting.split_once('=').unwrap_or((setting, ""));
+    if name == "search_path" || name == "temp_tablespaces" {
+        format!("SET {} TO {}", name, value)
+    } else {
+        format!("SET {} TO {}", name, quote_literal(value))
+    }
+}
+
+/// Owner and grants of a routine that was just created. New routines are
+/// executable by `public` unless that is revoked. Without a known owner (not
+/// recorded, or an ignored role) the defaults are left alone.
+fn created_routine_privileges(routine: &Routine) -> Vec<String> {
+    let Some(owner) = &routine.owner else {
+        return Vec::new();
+    };
+    let object = routine_object(routine);
+    let default = [Grant {
+        grantee: "public".to_string(),
+        privilege: "EXECUTE".to_string(),
+        grantable: false,
+    }];
+    let (added, removed) = diff_grants(&default, &routine.grants);
+
+    let mut statements = vec![alter_owner(&object, owner)];
+    statements.extend(grant_changes(&object, None, &added, &removed));
+    statements
+}
+
+/// Owner and grants of a view that was just created, which start out as the
+/// current role's with no grants. `keyword` is `VIEW` or `MATERIALIZED VIEW`.
+fn created_view_privileges(
+    keyword: &str,
+    schema: &str,
+    name: &str,
+    owner: &Option<String>,
+    grants: &[Grant],
+    columns: &[Column],
+) -> Vec<String> {
+    let name = qualified(schema, name);
+    let mut statements = Vec::new();
+    if let Some(owner) = owner {
+        statements.push(alter_owner(&format!("{} {}", keyword, name), owner));
+    }
+    // GRANT takes views as tables
+    let object = format!("TABLE {}", name);
+    statements.extend(grant_changes(&object, None, grants, &[]));
+    for column in columns {
+        statements.extend(grant_changes(&object, Some(&column.name), &column.grants, &[]));
+    }
+    statements
+}
+
+fn view_privilege_changes(keyword: &str, diff: &ViewDiff) -> Vec<String> {
+    if diff.needs_rebuild() {
+        return created_view_privileges(
+            keyword,
+            &diff.schema_name,
+            &diff.view_name,
+            &diff.new_owner,
+            &diff.new_grants,
+            &diff.new_columns,
+        );
+    }
+
+    let name = qualified(&diff.schema_name, &diff.view_name);
+    let mut statements = Vec::new();
+    if let (true, Some(owner)) = (diff.owner_changed(), &diff.new_owner) {
+        statements.push(alter_owner(&format!("{} {}", keyword, name), owner));
+    }
+    let object = format!("TABLE {}", name);
+    let (added, removed) = diff_grants(&diff.old_grants, &diff.new_grants);
+    statements.extend(grant_changes(&object, None, &added, &removed));
+    for column_diff in &diff.columns_modified {
+        let (added, removed) = diff_grants(&column_diff.old.grants, &column_diff.new.grants);
+        statements.extend(grant_changes(&object, Some(&column_diff.column_name), &added, &removed));
+    }
+    statements
+}
+
+/// e.g. `FUNCTION public.add(integer, integer)`, as used by GRANT and ALTER
+fn routine_object(routine: &Routine) -> String {
+    format!(
+        "{} {}({})",
+        routine_keyword(routine),
+        qualified(&routine.schema, &routine.name),
+        routine.argument_types
+    )
+}
+
+fn routine_keyword(routine: &Routine) -> &'static str {
+    match routine.kind {
+        RoutineKind::Function => "FUNCTION",
+        RoutineKind::Procedure => "PROCEDURE",
+    }
+}
+
+/// Picks a dollar-quote tag (`$body$`, `$body1$`, ...) that does not occur in `text`
+fn dollar_quote_tag(text: &str) -> String {
+    let mut tag = "$body$".to_string();
+    let mut n = 0;
+    while text.contains(&tag) {
+        n += 1;
+        tag = format!("$body{}$", n);
+    }
+    tag
+}
+
+fn drop_trigger(schema: &str, table: &str, trigger: &Trigger) -> String {
+    format!(
+        "DROP TRIGGER {} ON {};",
+        quote_ident(&trigger.name),
+        qualified(schema, table)
+    )
+}
+
+/// Recreates a trigger from its captured definition, then restores a non-default enabled state
+fn create_trigger(schema: &str, table: &str, trigger: &Trigger) -> Vec<String> {
+    let mut statements = vec![format!("{};", trigger.definition)];
+
+    if trigger.enabled != TriggerEnabled::Origin {
+        statements.push(alter_trigger_enabled(schema, table, trigger));
+    }
+
+    statements
+}
+
+fn alter_trigger_enabled(schema: &str, table: &str, trigger: &Trigger) -> String {
+    format!(
+        "ALTER TABLE {} {} TRIGGER {};",
+        qualified(schema, table),
+        enabled_action(&trigger.enabled),
+        quote_ident(&trigger.name)
+    )
+}
+
+fn create_policy(schema: &str, table: &str, policy: &Policy) -> String {
+    let command = match policy.command {
+        PolicyCommand::All => "ALL",
+        PolicyCommand::Select => "SELECT",
+        PolicyCommand::Insert => "INSERT",
+        PolicyCommand::Update => "UPDATE",
+        PolicyCommand::Delete => "DELETE",
+    };
+    format!(
+        "CREATE POLICY {} ON {} AS {} FOR {}{};",
+        quote_ident(&policy.name),
+        qualified(schema, table),
+        if policy.permissive { "PERMISSIVE" } else { "RESTRICTIVE" },
+        command,
+        policy_clauses(policy)
+    )
+}
+
+/// Updates roles and expressions in place; see `PolicyDiff::needs_recreate` for what it cannot change
+fn alter_policy(schema: &str, table: &str, policy: &Policy) -> String {
+    format!(
+        "ALTER POLICY {} ON {}{};",
+        quote_ident(&policy.name),
+        qualified(schema, table),
+        policy_clauses(policy)
+    )
+}
+
+/// ` TO ... USING (...) WITH CHECK (...)`. Roles are captured already quoted.
+fn policy_clauses(policy: &Policy) -> String {
+    let mut clauses = format!(" TO {}", policy.roles.join(", "));
+    if let Some(using) = &policy.using {
+        clauses.push_str(&format!(" USING ({})", using));
+    }
+    if let Some(with_check) = &policy.with_check {
+        clauses.push_str(&format!(" WITH CHECK ({})", with_check));
+    }
+    clauses
+}
+
+fn drop_policy(schema: &str, table: &str, name: &str) -> String {
+    format!("DROP POLICY {} ON {};", quote_ident(name), qualified(schema, table))
+}
+
+fn alter_rls(schema: &str, table: &str, action: &str) -> String {
+    format!("ALTER TABLE {} {};", qualified(schema, table), action)
+}
+
+/// `object` is a keyword and name, e.g. `TABLE public.users` or `SCHEMA audit`
+fn alter_owner(object: &str, owner: &str) -> String {
+    format!("ALTER {} OWNER TO {};", object, quote_ident(owner))
+}
+
+/// REVOKE statements for `removed` followed by GRANT statements for `added`
+/// on `object`, limited to `column` for column privileges
+fn grant_changes(object: &str, column: Option<&str>, added: &[Grant], removed: &[Grant]) -> Vec<String> {
+    let privilege = |grant: &Grant| match column {
+        Some(column) => format!("{} ({})", grant.privilege, quote_ident(column)),
+        None => grant.privilege.clone(),
+    };
+    let revokes = removed.iter().map(|grant| {
+        format!("REVOKE {} ON {} FROM {};", privilege(grant), object, quote_role(&grant.grantee))
+    });
+    let grants = added.iter().map(|grant| {
+        format!(
+            "GRANT {} ON {} TO {}{};",
+            privilege(grant),
+            object,
+            quote_role(&grant.grantee),
+            if grant.grantable { " WITH GRANT OPTION" } else { "" }
+        )
+    });
+    revokes.chain(grants).collect()
+}
+
+/// Grantee as written in GRANT and REVOKE, where `PUBLIC` is a keyword
+fn quote_role(role: &str) -> String {
+    if role == "public" {
+        "PUBLIC".to_string()
+    } else {
+        quote_ident(role)
+    }
+}
+
+/// Event triggers cannot be altered beyond their enabled state, so any other change recreates them
+fn event_trigger_redefined(diff: &EventTriggerDiff) -> bool {
+    diff.old.event != diff.new.event
+        || diff.old.tags != diff.new.tags
+        || diff.old.function != diff.new.function
+}
+
+fn create_event_trigger(trigger: &EventTrigger) -> Vec<String> {
+    let mut create = format!(
+        "CREATE EVENT TRIGGER {} ON {}",
+        quote_ident(&trigger.name),
+        quote_ident(&trigger.event)
+    );
+
+    if !trigger.tags.is_empty() {
+        let tags: Vec<String> = trigger
+            .tags
+            .iter()
+            .map(|tag| quote_literal(tag))
+            .collect();
+        create.push_str(&format!("\n    WHEN TAG IN ({})", tags.join(", ")));
+    }
+
+    create.push_str(&format!("\n    EXECUTE FUNCTION {}();", trigger.function));
+
+    let mut statements = vec![create];
+    if trigger.enabled != TriggerEnabled::Origin {
+        statements.push(alter_event_trigger_enabled(trigger));
+    }
+
+    statements
+}
+
+fn alter_event_trigger_enabled(trigger: &EventTrigger) -> String {
+    format!(
+        "ALTER EVENT TRIGGER {} {};",
+        quote_ident(&trigger.name),
+        enabled_action(&trigger.enabled)
+    )
+}
+
+fn enabled_action(enabled: &TriggerEnabled) -> &'static str {
+    match enabled {
+        TriggerEnabled::Origin => "ENABLE",
+        TriggerEnabled::Disabled => "DISABLE",
+        TriggerEnabled::Replica => "ENABLE REPLICA",
+        TriggerEnabled::Always => "ENABLE ALWAYS",
+    }
+}
+
+fn column_definition(column: &Column) -> String {
+    let mut definition = format!(
+        "{} {}{}",
+        quote_ident(&column.name),
+        column.data_type,
+        collate_clause(column)
+    );
+
+    if let Some(default) = &column.default_value {
+        definition.push_str(&format!(" DEFAULT {}", default));
+    }
+
+    if let Some(generated) = &column.generated {
+        definition.push_str(&format!(" {}", generated_column_clause(generated)));
+    }
+
+    if let Some(identity) = &column.identity {
+        definition.push_str(&format!(" {}", identity_clause(identity)));
+    }
+
+    if !column.is_nullable {
+        definition.push_str(" NOT NULL");
+    }
+
+    definition
+}
+
+fn generated_column_clause(generated: &GeneratedColumn) -> String {
+    let kind = match generated.kind {
+        GeneratedKind::Stored => "STORED",
+        GeneratedKind::Virtual => "VIRTUAL",
+    };
+    format!("GENERATED ALWAYS AS ({}) {}", generated.expression, kind)
+}
+
+fn collate_clause(column: &Column) -> String {
+    match (&column.collation_schema, &column.collation) {
+        (Some(schema), Some(collation)) => format!(" COLLATE {}", qualified(schema, collation)),
+        (None, Some(collation)) => format!(" COLLATE {}", quote_ident(collation)),
+        (_, None) => String::new(),
+    }
+}
+
+fn create_index(schema: &str, table: &str, index: &Index) -> String {
+    let mut statement = String::new();
+    if !index.is_valid {
+        statement.push_str(&format!(
+            "-- WARNING: index {} is invalid in the target schema; this creates it as a valid index\n",
+            quote_ident(&index.name)
+        ));
+    }
+
+    statement.push_str(&format!(
+        "CREATE {}INDEX {} ON {}",
+        if index.is_unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name),
+        qualified(schema, table)
+    ));
+    if let Some(method) = index.method.as_deref().filter(|m| *m != "btree") {
+        statement.push_str(&format!(" USING {}", method));
+    }
+
+    let keys: Vec<String> = index
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| index_key(column, index.key_options.get(i)))
+        .collect();
+    statement.push_str(&format!(" ({})", keys.join(", ")));
+
+    if !index.include.is_empty() {
+        statement.push_str(&format!(" INCLUDE ({})", quote_list(&index.include)));
+    }
+    if let Some(predicate) = &index.predicate {
+        statement.push_str(&format!(" WHERE ({})", predicate));
+    }
+    statement.push(';');
+
+    statement
+}
+
+/// One index key with its operator class and ordering. Snapshots taken
+/// before key options were recorded only have plain ascending columns.
+fn index_key(column: &str, options: Option<&IndexKeyOptions>) -> String {
+    let Some(options) = options else {
+        return quote_ident(column);
+    };
+
+    // Expression text is printed by the server with any parentheses it needs
+    let mut key = if options.expression {
+        column.to_string()
+    } else {
+        quote_ident(column)
+    };
+    if let Some(opclass) = &options.opclass {
+        key.push_str(&format!(" {}", opclass));
+    }
+    match (options.descending, options.nulls_first) {
+        (false, true) => key.push_str(" NULLS FIRST"),
+        (true, true) => key.push_str(" DESC"),
+        (true, false) => key.push_str(" DESC NULLS LAST"),
+        (false, false) => {}
+    }
+
+    key
+}
+
+fn add_constraint(schema: &str, table: &str, constraint: &Constraint) -> String {
+    match constraint_definition(schema, constraint) {
+        Some(definition) => format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {}{};",
+            qualified(schema, table),
+            quote_ident(&constraint.name),
+            definition,
+            if constraint.not_valid { " NOT VALID" } else { "" }
+        ),
+        None => format!(
+            "-- WARNING: cannot recreate constraint {} on {}: its definition was not captured",
+            quote_ident(&constraint.name),
+            qualified(schema, table)
+        ),
+    }
+}
+
+fn drop_constraint(schema: &str, table: &str, name: &str) -> String {
+    format!(
+        "ALTER TABLE {} DROP CONSTRAINT {};",
+        qualified(schema, table),
+        quote_ident(name)
+    )
+}
+
+/// Constraint body after `CONSTRAINT name`, or None if it cannot be reproduced
+fn constraint_definition(schema: &str, constraint: &Constraint) -> Option<String> {
+    let columns = quote_list(&constraint.columns);
+
+    let mut definition = match constraint.constraint_type {
+        ConstraintType::PrimaryKey => format!("PRIMARY KEY ({})", columns),
+        ConstraintType::Unique => format!("UNIQUE ({})", columns),
+        ConstraintType::ForeignKey => foreign_key_definition(schema, constraint),
+        // Snapshots taken before definitions were recorded cannot reproduce these
+        ConstraintType::Check | ConstraintType::Exclusion | ConstraintType::NotNull => {
+            constraint.definition.clone()?
+        }
+    };
+
+    if constraint.deferrable {
+        definition.push_str(" DEFERRABLE");
+        if constraint.initially_deferred {
+            definition.push_str(" INITIALLY DEFERRED");
+        }
+    }
+
+    Some(definition)
+}
+
+fn foreign_key_definition(schema: &str, constraint: &Constraint) -> String {
+    // Snapshots taken before the referenced schema was recorded assume the table's own
+    let foreign_schema = constraint.foreign_schema.as_deref().unwrap_or(schema);
+    let mut definition = format!(
+        "FOREIGN KEY ({}) REFERENCES {} ({})",
+        quote_list(&constraint.columns),
+        qualified(foreign_schema, constraint.foreign_table.as_deref().unwrap_or_default()),
+        quote_list(constraint.foreign_columns.as_deref().unwrap_or_default())
+    );
+
+    match constraint.match_type {
+        Some(ForeignKeyMatch::Full) => definition.push_str(" MATCH FULL"),
+        Some(ForeignKeyMatch::Partial) => definition.push_str(" MATCH PARTIAL"),
+        _ => {}
+    }
+
+    for (event, action) in [("UPDATE", &constraint.on_update), ("DELETE", &constraint.on_delete)] {
+        let action = match action {
+            Some(ForeignKeyAction::Restrict) => "RESTRICT",
+            Some(ForeignKeyAction::Cascade) => "CASCADE",
+            Some(ForeignKeyAction::SetNull) => "SET NULL",
+            Some(ForeignKeyAction::SetDefault) => "SET DEFAULT",
+            Some(ForeignKeyAction::NoAction) | None => continue,
+        };
+        definition.push_str(&format!(" ON {} {}", event, action));
+    }
+
+    definition
+}
+
+/// Key constraints that are declared inside CREATE TABLE
+fn is_inline(constraint: &Constraint) -> bool {
+    matches!(
+        constraint.constraint_type,
+        ConstraintType::PrimaryKey | ConstraintType::Unique
+    )
+}
+
+fn foreign_keys(constraints: &[Constraint]) -> impl Iterator<Item = &Constraint> {
+    constraints.iter().filter(|c| is_foreign_key(c))
+}
+
+fn is_foreign_key(constraint: &Constraint) -> bool {
+    constraint.constraint_type == ConstraintType::ForeignKey
+}
+
+/// NOT NULL constraints follow their column's nullability, which the column
+/// changes already apply
+fn is_not_null(constraint: &Constraint) -> bool {
+    constraint.constraint_type == ConstraintType::NotNull
+}
+
+fn qualified(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
+
+fn quote_list(names: &[String]) -> String {
+    names.iter().map(|n| quote_ident(n)).collect::<Vec<_>>().join(", ")
+}
+
+/// Quotes a string literal, e.g. an enum label or event trigger tag
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Quotes an identifier if PostgreSQL would not accept it bare
+pub fn quote_ident(name: &str) -> String {
+    let is_simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if is_simple && !RESERVED_KEYWORDS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::models::{Partitioning, PartitionBound, Schema};
+
+    fn column(name: &str, data_type: &str, position: i32) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: position,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants: Vec::new(),
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> Table {
+        Table {
+            name: name.to_string(),
+            schema: "public".to_string(),
+            columns,
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            partition_of: None,
+            rls_enabled: false,
+            rls_forced: false,
+            policies: Vec::new(),
+            owner: None,
+            grants: Vec::new(),
+        }
+    }
+
+    fn foreign_key(name: &str, column: &str, foreign_table: &str) -> Constraint {
+        Constraint {
+            name: name.to_string(),
+            constraint_type: ConstraintType::ForeignKey,
+            columns: vec![column.to_string()],
+            foreign_schema: Some("public".to_string()),
+            foreign_table: Some(foreign_table.to_string()),
+            foreign_columns: Some(vec!["id".to_string()]),
+            on_update: None,
+            on_delete: None,
+            match_type: None,
+            deferrable: false,
+            initially_deferred: false,
+            not_valid: false,
+            definition: None,
+        }
+    }
+
+    fn partition(name: &str, parent: &str, bound: &str) -> Table {
+        Table {
+            partition_of: Some(PartitionBound {
+                parent_schema: "public".to_string(),
+                parent_table: parent.to_string(),
+                bound: bound.to_string(),
+            }),
+            ..table(name, Vec::new())
+        }
+    }
+
+    fn schema(tables: Vec<Table>) -> Schema {
+        Schema {
+            tables,
+            ..Schema::new()
+        }
+    }
+
+    /// Index of the first statement starting with `prefix`
+    fn position(statements: &[String], prefix: &str) -> usize {
+        statements
+            .iter()
+            .position(|s| s.starts_with(prefix))
+            .unwrap_or_else(|| panic!("no statement starts with {:?} in {:#?}", prefix, statements))
+    }
+
+    #[test]
+    fn drops_foreign_keys_before_their_tables() {
+        let customers = table("customers", vec![column("id", "integer", 1)]);
+        let mut orders = table("orders", vec![column("id", "integer", 1), column("customer_id", "integer", 2)]);
+        orders.constraints.push(foreign_key("orders_customer_id_fkey", "customer_id", "customers"));
+        let diff = SchemaDiff::compare(&schema(vec![customers, orders]), &Schema::new());
+
+        let statements = generate_migration(&diff).unwrap();
+        let drop_fkey = position(&statements, "ALTER TABLE public.orders DROP CONSTRAINT orders_customer_id_fkey;");
+
+        assert!(drop_fkey < position(&statements, "DROP TABLE public.customers;"));
+        assert!(drop_fkey < position(&statements, "DROP TABLE public.orders;"));
+    }
+
+    #[test]
+    fn creates_types_before_columns_that_use_them() {
+        let old = schema(vec![table("orders", vec![column("id", "integer", 1)])]);
+        let mut new = schema(vec![
+            table("orders", vec![column("id", "integer", 1), column("status", "public.order_status", 2)]),
+            table("returns", vec![column("status", "public.order_status", 1)]),
+        ]);
+        new.enums.push(EnumType {
+            name: "order_status".to_string(),
+            schema: "public".to_string(),
+            labels: vec!["open".to_string(), "closed".to_string()],
+        });
+        let diff = SchemaDiff::compare(&old, &new);
+
+        let statements = generate_migration(&diff).unwrap();
+        let create_type = position(&statements, "CREATE TYPE public.order_status AS ENUM");
+
+        assert!(create_type < position(&statements, "ALTER TABLE public.orders ADD COLUMN status"));
+        assert!(create_type < position(&statements, "CREATE TABLE public.returns ("));
+    }
+
+    #[test]
+    fn creates_partitions_after_their_parents() {
+        let mut events = table("events", vec![column("created_at", "date", 1)]);
+        events.partitioning = Some(Partitioning {
+            strategy: PartitionStrategy::Range,
+            key: "created_at".to_string(),
+        });
+        let mut events_2024 = partition("events_2024", "events", "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')");
+        events_2024.partitioning = events.partitioning.clone();
+        let events_2024_01 = partition(
+            "events_2024_01",
+            "events_2024",
+            "FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')",
+        );
+        // Listed children first, so the order comes from the migration and not the input
+        let diff = SchemaDiff::compare(&Schema::new(), &schema(vec![events_2024_01, events_2024, events]));
+
+        let statements = generate_migration(&diff).unwrap();
+        let parent = position(&statements, "CREATE TABLE public.events (");
+        let child = position(&statements, "CREATE TABLE public.events_2024 PARTITION OF public.events");
+        let grandchild = position(&statements, "CREATE TABLE public.events_2024_01 PARTITION OF public.events_2024");
+
+        assert!(parent < child);
+        assert!(child < grandchild);
+    }
+
+    #[test]
+    fn creates_sequences_before_defaults() {
+        let old = schema(vec![table("orders", vec![column("id", "integer", 1)])]);
+        let mut number = column("number", "bigint", 2);
+        number.default_value = Some("nextval('public.order_number_seq'::regclass)".to_string());
+        let mut new = schema(vec![table("orders", vec![column("id", "integer", 1), number])]);
+        new.sequences.push(Sequence {
+            name: "order_number_seq".to_string(),
+            schema: "public".to_string(),
+            data_type: "bigint".to_string(),
+            start_value: 1,
+            increment: 1,
+            min_value: 1,
+            max_value: i64::MAX,
+            cycle: false,
+            owned_by: Some(SequenceOwner {
+                schema: "public".to_string(),
+                table: "orders".to_string(),
+                column: "number".to_string(),
+            }),
+            owner: None,
+            grants: Vec::new(),
+        });
+        let diff = SchemaDiff::compare(&old, &new);
+
+        let statements = generate_migration(&diff).unwrap();
+        let create_sequence = position(&statements, "CREATE SEQUENCE public.order_number_seq");
+        let add_column = position(&statements, "ALTER TABLE public.orders ADD COLUMN number");
+        let owned_by = position(&statements, "ALTER SEQUENCE public.order_number_seq OWNED BY public.orders.number;");
+
+        assert!(create_sequence < add_column);
+        assert!(add_column < owned_by);
+    }
+
+    /// Schemas before and after a change that adds a table, an enum label and
+    /// a column, widens a column and drops an index
+    fn widening_change() -> (Schema, Schema) {
+        let mut orders = table(
+            "orders",
+            vec![column("id", "integer", 1), column("code", "character varying(50)", 2)],
+        );
+        orders.indexes.push(Index {
+            name: "orders_code_idx".to_string(),
+            columns: vec!["code".to_string()],
+            is_unique: false,
+            is_primary: false,
+            method: Some("btree".to_string()),
+            key_options: Vec::new(),
+            include: Vec::new(),
+            predicate: None,
+            is_valid: true,
+            backs_constraint: false,
+        });
+        let status = EnumType {
+            name: "order_status".to_string(),
+            schema: "public".to_string(),
+            labels: vec!["open".to_string()],
+        };
+        let mut old = schema(vec![orders]);
+        old.enums.push(status.clone());
+
+        let mut new = schema(vec![
+            table(
+                "orders",
+                vec![
+                    column("id", "integer", 1),
+                    column("code", "character varying(255)", 2),
+                    column("notes", "text", 3),
+                ],
+            ),
+            table("audit", vec![column("id", "integer", 1)]),
+        ]);
+        new.enums.push(EnumType {
+            labels: vec!["open".to_string(), "closed".to_string()],
+            ..status
+        });
+
+        (old, new)
+    }
+
+    #[test]
+    fn rollback_is_migration_of_inverted_diff() {
+        let (old, new) = widening_change();
+        let diff = SchemaDiff::compare(&old, &new);
+
+        let rollback = generate_rollback(&diff).unwrap();
+
+        assert_eq!(rollback, generate_migration(&diff.invert()).unwrap());
+        assert_eq!(rollback, generate_migration(&SchemaDiff::compare(&new, &old)).unwrap());
+        assert!(rollback.iter().any(|s| s.starts_with("CREATE INDEX orders_code_idx ON public.orders")));
+    }
+
+    #[test]
+    fn rollback_warns_about_lossy_steps() {
+        let (old, new) = widening_change();
+        let diff = SchemaDiff::compare(&old, &new);
+
+        let migration = generate_migration(&diff).unwrap();
+        let rollback = generate_rollback(&diff).unwrap();
+
+        for warning in [
+            "-- WARNING: irreversible, drops table public.audit",
+            "-- WARNING: irreversible, drops column notes of public.orders",
+            "-- WARNING: converting code from character varying(255) to character varying(50) may fail or lose data",
+            "-- WARNING: cannot remove or reorder labels of enum public.order_status",
+        ] {
+            assert!(rollback.iter().any(|s| s.starts_with(warning)), "{:?} missing from {:#?}", warning, rollback);
+        }
+        assert!(!migration.iter().any(|s| s.starts_with("-- WARNING")), "{:#?}", migration);
+    }
+
+    #[test]
+    fn drops_sequences_owned_by_dropped_partitions_once() {
+        let mut events = table("events", vec![column("id", "bigint", 1)]);
+        events.partitioning = Some(Partitioning {
+            strategy: PartitionStrategy::List,
+            key: "id".to_string(),
+        });
+        let mut old = schema(vec![events.clone(), partition("events_1", "events", "FOR VALUES IN (1)")]);
+        old.sequences.push(Sequence {
+            name: "events_1_seq".to_string(),
+            schema: "public".to_string(),
+            data_type: "bigint".to_string(),
+            start_value: 1,
+            increment: 1,
+            min_value: 1,
+            max_value: i64::MAX,
+            cycle: false,
+            owned_by: Some(SequenceOwner {
+                schema: "public".to_string(),
+                table: "events_1".to_string(),
+                column: "id".to_string(),
+            }),
+            owner: None,
+            grants: Vec::new(),
+        });
+        let diff = SchemaDiff::compare(&old, &schema(vec![events]));
+
+        let statements = generate_migration(&diff).unwrap();
+
+        assert!(statements.contains(&"DROP TABLE public.events_1;".to_string()));
+        assert!(!statements.iter().any(|s| s.starts_with("DROP SEQUENCE")), "{:#?}", statements);
+    }
+
+    #[test]
+    fn qualifies_and_quotes_collations() {
+        let collated = |schema: Option<&str>, collation: &str| Column {
+            collation: Some(collation.to_string()),
+            collation_schema: schema.map(str::to_string),
+            ..column("name", "text", 1)
+        };
+
+        assert_eq!(collate_clause(&collated(Some("pg_catalog"), "C")), r#" COLLATE pg_catalog."C""#);
+        assert_eq!(collate_clause(&collated(Some("Shared"), "de_phonebook")), r#" COLLATE "Shared".de_phonebook"#);
+        assert_eq!(collate_clause(&collated(None, "en-US")), r#" COLLATE "en-US""#);
+        assert_eq!(collate_clause(&column("name", "text", 1)), "");
+    }
+
+    #[test]
+    fn rejects_partitions_without_a_parent() {
+        let mut diff = SchemaDiff::compare(&Schema::new(), &Schema::new());
+        diff.partitions_added.push(table("orphan", Vec::new()));
+
+        let error = generate_migration(&diff).unwrap_err();
+
+        assert!(error.to_string().contains("public.orphan has no parent"), "{}", error);
+    }
+}
@@ -1,5 +1,16 @@
 use colored::*;
-use crate::diff::engine::{SchemaDiff, TableDiff, ColumnDiff};
+use crate::diff::engine::{
+    SchemaDiff, TableDiff, SequenceDiff, EnumDiff, DomainDiff, CompositeTypeDiff, ViewDiff, RoutineDiff,
+    ColumnDiff, TypeChange, IndexDiff, ConstraintDiff, TriggerDiff, EventTriggerDiff, DiffStats,
+    SchemaPrivilegesDiff, diff_grants,
+};
+use crate::diff::text::{diff_lines, LineChange};
+use crate::schema::models::{
+    Column, CompositeType, Constraint, ConstraintType, Domain, EnumType, EventTrigger, ForeignKeyAction,
+    ForeignKeyMatch, GeneratedColumn, GeneratedKind, Grant, Identity, Index, ParallelSafety, PartitionBound, PartitionStrategy,
+    Partitioning, Policy, PolicyCommand, Routine, RoutineKind, Sequence, SequenceOwner, Table, Trigger, TriggerEnabled,
+    TriggerTiming,
+};
 
 /// Prints a schema diff to the terminal with colors
 pub fn print_diff(diff: &SchemaDiff) {
@@ -8,55 +19,822 @@ pub fn print_diff(diff: &SchemaDiff) {
         return;
     }
 
+    // Print added, removed and re-granted schemas
+    for name in &diff.schemas_added {
+        println!("{} schema {}", "+".green().bold(), name.green().bold());
+        for privileges in diff.schema_privileges_added.iter().filter(|p| &p.name == name) {
+            print_grant_changes("  ", &privileges.grants, &[]);
+        }
+    }
+
+    for name in &diff.schemas_removed {
+        println!("{} schema {}", "-".red().bold(), name.red().bold());
+    }
+
+    for privileges_diff in &diff.schema_privileges_modified {
+        print_schema_privileges_diff(privileges_diff);
+    }
+
     // Print added tables
     for table in &diff.tables_added {
-        println!("{} {}", "+".green().bold(), table.name.green());
+        println!("{} {}{}", "+".green().bold(), table.qualified_name().green(), describe_partitioned(table));
         for col in &table.columns {
-            println!("  {} {} ({})", "+".green(), col.name, col.data_type);
+            println!("  {} {} ({})", "+".green(), col.name, describe_column_type(col));
         }
+        print_table_rls(table, "+".green());
+        print_relation_grants(&table.grants, &table.columns);
+        print_partition_changes(diff, &table.qualified_name());
     }
 
     // Print removed tables
     for table in &diff.tables_removed {
-        println!("{} {}", "-".red().bold(), table.name.red());
+        println!("{} {}{}", "-".red().bold(), table.qualified_name().red(), describe_partitioned(table));
         for col in &table.columns {
-            println!("  {} {} ({})", "-".red(), col.name, col.data_type);
+            println!("  {} {} ({})", "-".red(), col.name, describe_column_type(col));
         }
+        print_table_rls(table, "-".red());
+        print_partition_changes(diff, &table.qualified_name());
     }
 
     // Print modified tables
     for table_diff in &diff.tables_modified {
         print_table_diff(table_diff);
+        print_partition_changes(diff, &table_diff.qualified_name());
+    }
+
+    // Print partitions created or dropped under a parent that is otherwise unchanged
+    let mut parents: Vec<String> = diff
+        .partitions_added
+        .iter()
+        .chain(&diff.partitions_removed)
+        .filter_map(|p| p.partition_of.as_ref().map(PartitionBound::parent_name))
+        .collect();
+    parents.sort();
+    parents.dedup();
+    for parent in parents {
+        let listed = diff
+            .tables_added
+            .iter()
+            .chain(&diff.tables_removed)
+            .map(Table::qualified_name)
+            .chain(diff.tables_modified.iter().map(TableDiff::qualified_name))
+            .any(|name| name == parent);
+        if !listed {
+            println!("{} {}", "~".yellow().bold(), parent.yellow());
+            print_partition_changes(diff, &parent);
+        }
+    }
+
+    // Print sequences
+    for sequence in &diff.sequences_added {
+        println!("{} sequence {}", "+".green().bold(), describe_sequence(sequence).green());
+    }
+
+    for sequence in &diff.sequences_removed {
+        println!("{} sequence {}", "-".red().bold(), describe_sequence(sequence).red());
+    }
+
+    for sequence_diff in &diff.sequences_modified {
+        print_sequence_diff(sequence_diff);
+    }
+
+    // Print user-defined types
+    for enum_type in &diff.enums_added {
+        println!("{} enum {}", "+".green().bold(), describe_enum(enum_type).green());
+    }
+
+    for enum_type in &diff.enums_removed {
+        println!("{} enum {}", "-".red().bold(), describe_enum(enum_type).red());
+    }
+
+    for enum_diff in &diff.enums_modified {
+        print_enum_diff(enum_diff);
+    }
+
+    for domain in &diff.domains_added {
+        println!("{} domain {}", "+".green().bold(), describe_domain(domain).green());
+    }
+
+    for domain in &diff.domains_removed {
+        println!("{} domain {}", "-".red().bold(), describe_domain(domain).red());
+    }
+
+    for domain_diff in &diff.domains_modified {
+        print_domain_diff(domain_diff);
+    }
+
+    for composite in &diff.composite_types_added {
+        println!("{} type {}", "+".green().bold(), describe_composite_type(composite).green());
+    }
+
+    for composite in &diff.composite_types_removed {
+        println!("{} type {}", "-".red().bold(), describe_composite_type(composite).red());
+    }
+
+    for composite_diff in &diff.composite_types_modified {
+        print_composite_type_diff(composite_diff);
+    }
+
+    // Print views and materialized views
+    for view in &diff.views_added {
+        println!("{} view {}", "+".green().bold(), view.qualified_name().green());
+        print_relation_grants(&view.grants, &view.columns);
+    }
+
+    for view in &diff.views_removed {
+        println!("{} view {}", "-".red().bold(), view.qualified_name().red());
+    }
+
+    for view_diff in &diff.views_modified {
+        print_view_diff("view", view_diff);
+    }
+
+    for view in &diff.materialized_views_added {
+        println!("{} materialized view {}", "+".green().bold(), view.qualified_name().green());
+        print_relation_grants(&view.grants, &view.columns);
+    }
+
+    for view in &diff.materialized_views_removed {
+        println!("{} materialized view {}", "-".red().bold(), view.qualified_name().red());
+    }
+
+    for view_diff in &diff.materialized_views_modified {
+        print_view_diff("materialized view", view_diff);
+    }
+
+    // Print functions and procedures
+    for routine in &diff.routines_added {
+        println!("{} {} {}", "+".green().bold(), routine_kind(routine), routine.signature().green());
+    }
+
+    for routine in &diff.routines_removed {
+        println!("{} {} {}", "-".red().bold(), routine_kind(routine), routine.signature().red());
+    }
+
+    for routine_diff in &diff.routines_modified {
+        print_routine_diff(routine_diff);
+    }
+
+    // Print event triggers
+    for trigger in &diff.event_triggers_added {
+        println!("{} event trigger {}", "+".green().bold(), describe_event_trigger(trigger).green());
+    }
+
+    for trigger in &diff.event_triggers_removed {
+        println!("{} event trigger {}", "-".red().bold(), describe_event_trigger(trigger).red());
+    }
+
+    for trigger_diff in &diff.event_triggers_modified {
+        print_event_trigger_diff(trigger_diff);
+    }
+
+    // Row-level security changes decide who can see which rows, so they are
+    // called out again after the full diff
+    let rls_tables: Vec<String> = diff
+        .tables_modified
+        .iter()
+        .filter(|t| t.rls_changed())
+        .map(TableDiff::qualified_name)
+        .collect();
+    if !rls_tables.is_empty() {
+        println!(
+            "\n{} {}",
+            "!".red().bold(),
+            format!("Row-level security changed on {}; review before applying", rls_tables.join(", ")).red().bold()
+        );
+    }
+
+    let public_objects = objects_granted_to_public(diff);
+    if !public_objects.is_empty() {
+        println!(
+            "\n{} {}",
+            "!".red().bold(),
+            format!("New grants to public on {}; review before applying", public_objects.join(", ")).red().bold()
+        );
+    }
+}
+
+/// Objects that gain a privilege for every role. Routines are executable by
+/// public by default, so new routines are left out.
+fn objects_granted_to_public(diff: &SchemaDiff) -> Vec<String> {
+    let any_public = |grants: &[Grant]| grants.iter().any(|g| g.grantee == "public");
+    let relation_public = |grants: &[Grant], columns: &[Column]| {
+        any_public(grants) || columns.iter().any(|c| any_public(&c.grants))
+    };
+    let table_public = |table: &Table| relation_public(&table.grants, &table.columns);
+    let mut objects = Vec::new();
+
+    for privileges in &diff.schema_privileges_added {
+        if any_public(&privileges.grants) {
+            objects.push(format!("schema {}", privileges.name));
+        }
+    }
+    for privileges_diff in &diff.schema_privileges_modified {
+        if any_public(&diff_grants(&privileges_diff.old.grants, &privileges_diff.new.grants).0) {
+            objects.push(format!("schema {}", privileges_diff.schema_name));
+        }
+    }
+    for table in diff.tables_added.iter().chain(&diff.partitions_added).filter(|t| table_public(t)) {
+        objects.push(table.qualified_name());
+    }
+    for table_diff in &diff.tables_modified {
+        let columns_public = table_diff.columns_added.iter().any(|c| any_public(&c.grants))
+            || table_diff
+                .columns_modified
+                .iter()
+                .any(|c| any_public(&diff_grants(&c.old.grants, &c.new.grants).0));
+        if any_public(&table_diff.grants_added) || columns_public {
+            objects.push(table_diff.qualified_name());
+        }
+    }
+    for view in diff.views_added.iter().filter(|v| relation_public(&v.grants, &v.columns)) {
+        objects.push(view.qualified_name());
+    }
+    for view in diff.materialized_views_added.iter().filter(|v| relation_public(&v.grants, &v.columns)) {
+        objects.push(view.qualified_name());
+    }
+    for view_diff in diff.views_modified.iter().chain(&diff.materialized_views_modified) {
+        // A rebuilt view is granted everything again
+        let public = if view_diff.needs_rebuild() {
+            relation_public(&view_diff.new_grants, &view_diff.new_columns)
+        } else {
+            any_public(&diff_grants(&view_diff.old_grants, &view_diff.new_grants).0)
+                || view_diff
+                    .columns_modified
+                    .iter()
+                    .any(|c| any_public(&diff_grants(&c.old.grants, &c.new.grants).0))
+        };
+        if public {
+            objects.push(view_diff.qualified_name());
+        }
+    }
+    for sequence in diff.sequences_added.iter().filter(|s| any_public(&s.grants)) {
+        objects.push(sequence.qualified_name());
+    }
+    for sequence_diff in &diff.sequences_modified {
+        if any_public(&diff_grants(&sequence_diff.old.grants, &sequence_diff.new.grants).0) {
+            objects.push(sequence_diff.sequence_name.clone());
+        }
+    }
+    for routine_diff in &diff.routines_modified {
+        if any_public(&diff_grants(&routine_diff.old.grants, &routine_diff.new.grants).0) {
+            objects.push(routine_diff.signature.clone());
+        }
+    }
+
+    objects
+}
+
+fn print_schema_privileges_diff(diff: &SchemaPrivilegesDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} schema {}", "~".yellow().bold(), diff.schema_name.yellow());
+
+    if diff.owner_changed() {
+        println!("    owner: {} -> {}", old.owner.red(), new.owner.green());
+    }
+
+    let (added, removed) = diff_grants(&old.grants, &new.grants);
+    print_grant_changes("  ", &added, &removed);
+}
+
+/// Grants of an added table or view, including column grants
+fn print_relation_grants(grants: &[Grant], columns: &[Column]) {
+    print_grant_changes("  ", grants, &[]);
+    for column in columns {
+        for grant in &column.grants {
+            print_grant("  ", &format!("{} ({})", grant.privilege, column.name), grant, true);
+        }
+    }
+}
+
+/// One line per privilege granted or revoked. New grants to public are
+/// flagged, as they open the object to every role.
+fn print_grant_changes(indent: &str, added: &[Grant], removed: &[Grant]) {
+    for grant in removed {
+        print_grant(indent, &grant.privilege, grant, false);
+    }
+    for grant in added {
+        print_grant(indent, &grant.privilege, grant, true);
+    }
+}
+
+fn print_grant(indent: &str, privilege: &str, grant: &Grant, added: bool) {
+    let mut text = format!("grant {} to {}", privilege, grant.grantee);
+    if grant.grantable {
+        text.push_str(" WITH GRANT OPTION");
+    }
+
+    match (added, grant.grantee == "public") {
+        (true, true) => println!("{}{} {}", indent, "!".red().bold(), text.red().bold()),
+        (true, false) => println!("{}{} {}", indent, "+".green(), text.green()),
+        (false, _) => println!("{}{} {}", indent, "-".red(), text.red()),
     }
 }
 
 fn print_table_diff(diff: &TableDiff) {
-    println!("{} {}", "~".yellow().bold(), diff.table_name.yellow());
+    println!("{} {}", "~".yellow().bold(), diff.qualified_name().yellow());
+
+    if diff.old_rls_enabled != diff.new_rls_enabled {
+        let text = if diff.new_rls_enabled {
+            "row-level security enabled"
+        } else {
+            "row-level security DISABLED"
+        };
+        println!("  {} {}", "!".red().bold(), text.red().bold());
+    }
+
+    if diff.old_rls_forced != diff.new_rls_forced {
+        let text = if diff.new_rls_forced {
+            "row-level security forced for the table owner"
+        } else {
+            "row-level security NO LONGER FORCED for the table owner"
+        };
+        println!("  {} {}", "!".red().bold(), text.red().bold());
+    }
+
+    if diff.partitioning_changed() {
+        println!("  {} partitioning", "~".yellow());
+        println!("    {}", describe_partitioning(diff.old_partitioning.as_ref()).red());
+        println!("    {}", describe_partitioning(diff.new_partitioning.as_ref()).green());
+    }
+
+    if diff.partition_bound_changed() {
+        println!("  {} partition of", "~".yellow());
+        println!("    {}", describe_partition_of(diff.old_partition_of.as_ref()).red());
+        println!("    {}", describe_partition_of(diff.new_partition_of.as_ref()).green());
+    }
+
+    for col in &diff.columns_added {
+        println!("  {} {} ({})", "+".green(), col.name.green(), describe_column_type(col));
+    }
+
+    for col in &diff.columns_removed {
+        println!("  {} {} ({})", "-".red(), col.name.red(), describe_column_type(col));
+    }
+
+    for col_diff in &diff.columns_modified {
+        print_column_diff(col_diff);
+    }
+
+    for idx in &diff.indexes_added {
+        println!("  {} index {}", "+".green(), describe_index(idx).green());
+    }
+
+    for idx in &diff.indexes_removed {
+        println!("  {} index {}", "-".red(), describe_index(idx).red());
+    }
+
+    for idx_diff in &diff.indexes_modified {
+        print_index_diff(idx_diff);
+    }
+
+    for con in &diff.constraints_added {
+        println!("  {} constraint {}", "+".green(), describe_constraint(con).green());
+    }
+
+    for con in &diff.constraints_removed {
+        println!("  {} constraint {}", "-".red(), describe_constraint(con).red());
+    }
+
+    for con_diff in &diff.constraints_modified {
+        print_constraint_diff(con_diff);
+    }
+
+    for trigger in &diff.triggers_added {
+        println!("  {} trigger {}", "+".green(), describe_trigger(trigger).green());
+    }
+
+    for trigger in &diff.triggers_removed {
+        println!("  {} trigger {}", "-".red(), describe_trigger(trigger).red());
+    }
+
+    for trigger_diff in &diff.triggers_modified {
+        print_trigger_diff(trigger_diff);
+    }
+
+    for policy in &diff.policies_added {
+        println!("  {} policy {}", "+".green(), describe_policy(policy).green().bold());
+    }
+
+    for policy in &diff.policies_removed {
+        println!("  {} policy {}", "-".red(), describe_policy(policy).red().bold());
+    }
+
+    for policy_diff in &diff.policies_modified {
+        println!("  {} policy {}", "~".yellow(), policy_diff.policy_name.yellow().bold());
+        println!("    {}", describe_policy(&policy_diff.old).red());
+        println!("    {}", describe_policy(&policy_diff.new).green());
+    }
+
+    if let (true, Some(old_owner), Some(new_owner)) = (diff.owner_changed(), &diff.old_owner, &diff.new_owner) {
+        println!("    owner: {} -> {}", old_owner.red(), new_owner.green());
+    }
+
+    print_grant_changes("  ", &diff.grants_added, &diff.grants_removed);
+}
+
+/// Row-level security state and policies of an added or removed table
+fn print_table_rls(table: &Table, sign: ColoredString) {
+    if table.rls_enabled {
+        let forced = if table.rls_forced { ", forced" } else { "" };
+        println!("  {} row-level security enabled{}", "!".red().bold(), forced);
+    }
+
+    for policy in &table.policies {
+        println!("  {} policy {}", sign, describe_policy(policy));
+    }
+}
+
+/// Lists the partitions of `parent` that were created or dropped
+fn print_partition_changes(diff: &SchemaDiff, parent: &str) {
+    let of_parent = |table: &&Table| {
+        table
+            .partition_of
+            .as_ref()
+            .is_some_and(|p| p.parent_name() == parent)
+    };
+
+    for partition in diff.partitions_added.iter().filter(of_parent) {
+        println!("  {} partition {}", "+".green(), describe_partition(partition).green());
+    }
+
+    for partition in diff.partitions_removed.iter().filter(of_parent) {
+        println!("  {} partition {}", "-".red(), describe_partition(partition).red());
+    }
+}
+
+fn print_view_diff(kind: &str, diff: &ViewDiff) {
+    println!("{} {} {}", "~".yellow().bold(), kind, diff.qualified_name().yellow());
 
     for col in &diff.columns_added {
-        println!("  {} {} ({})", "+".green(), col.name.green(), col.data_type);
+        println!("  {} {} ({})", "+".green(), col.name.green(), describe_column_type(col));
     }
 
     for col in &diff.columns_removed {
-        println!("  {} {} ({})", "-".red(), col.name.red(), col.data_type);
+        println!("  {} {} ({})", "-".red(), col.name.red(), describe_column_type(col));
     }
 
     for col_diff in &diff.columns_modified {
         print_column_diff(col_diff);
     }
+
+    for idx in &diff.indexes_added {
+        println!("  {} index {}", "+".green(), describe_index(idx).green());
+    }
+
+    for idx in &diff.indexes_removed {
+        println!("  {} index {}", "-".red(), describe_index(idx).red());
+    }
+
+    for idx_diff in &diff.indexes_modified {
+        print_index_diff(idx_diff);
+    }
+
+    if diff.definition_changed() {
+        println!("  {} definition", "~".yellow());
+        print_text_diff(&diff.old_definition, &diff.new_definition);
+    }
+
+    print_owner_and_grant_changes(
+        diff.owner_changed(),
+        &diff.old_owner,
+        &diff.new_owner,
+        &diff.old_grants,
+        &diff.new_grants,
+    );
+}
+
+fn print_routine_diff(diff: &RoutineDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} {} {}", "~".yellow().bold(), routine_kind(new), diff.signature.yellow());
+
+    if old.kind != new.kind {
+        println!("    kind: {} -> {}", routine_kind(old).red(), routine_kind(new).green());
+    }
+
+    if old.arguments != new.arguments {
+        println!("    arguments: {} -> {}", old.arguments.red(), new.arguments.green());
+    }
+
+    if old.return_type != new.return_type {
+        let old_returns = old.return_type.as_deref().unwrap_or("(none)");
+        let new_returns = new.return_type.as_deref().unwrap_or("(none)");
+        println!("    returns: {} -> {}", old_returns.red(), new_returns.green());
+    }
+
+    if old.language != new.language {
+        println!("    language: {} -> {}", old.language.red(), new.language.green());
+    }
+
+    if old.volatility != new.volatility {
+        println!(
+            "    volatility: {} -> {}",
+            format!("{:?}", old.volatility).red(),
+            format!("{:?}", new.volatility).green()
+        );
+    }
+
+    if old.security_definer != new.security_definer {
+        let describe = |definer: bool| if definer { "SECURITY DEFINER" } else { "SECURITY INVOKER" };
+        println!(
+            "    security: {} -> {}",
+            describe(old.security_definer).red(),
+            describe(new.security_definer).green()
+        );
+    }
+
+    if old.strict != new.strict {
+        let describe = |strict: bool| if strict { "STRICT" } else { "CALLED ON NULL INPUT" };
+        println!("    null input: {} -> {}", describe(old.strict).red(), describe(new.strict).green());
+    }
+
+    if old.leakproof != new.leakproof {
+        let describe = |leakproof: bool| if leakproof { "LEAKPROOF" } else { "NOT LEAKPROOF" };
+        println!("    leakproof: {} -> {}", describe(old.leakproof).red(), describe(new.leakproof).green());
+    }
+
+    if old.parallel != new.parallel {
+        let describe = |parallel: &Option<ParallelSafety>| match parallel {
+            Some(parallel) => format!("{:?}", parallel),
+            None => "(none)".to_string(),
+        };
+        println!("    parallel: {} -> {}", describe(&old.parallel).red(), describe(&new.parallel).green());
+    }
+
+    let estimate = |estimate: Option<f32>| estimate.map_or("(none)".to_string(), |e| e.to_string());
+    if old.cost != new.cost {
+        println!("    cost: {} -> {}", estimate(old.cost).red(), estimate(new.cost).green());
+    }
+    if old.rows != new.rows {
+        println!("    rows: {} -> {}", estimate(old.rows).red(), estimate(new.rows).green());
+    }
+
+    for setting in old.config.iter().filter(|s| !new.config.contains(s)) {
+        println!("    {} SET {}", "-".red(), setting.red());
+    }
+    for setting in new.config.iter().filter(|s| !old.config.contains(s)) {
+        println!("    {} SET {}", "+".green(), setting.green());
+    }
+
+    if old.body != new.body {
+        println!("  {} body", "~".yellow());
+        print_text_diff(&old.body, &new.body);
+    }
+
+    // Clauses that are not captured on their own, such as SUPPORT or
+    // TRANSFORM, only show in the full definition
+    let rest_unchanged = Routine {
+        definition: old.definition.clone(),
+        owner: old.owner.clone(),
+        grants: old.grants.clone(),
+        ..new.clone()
+    } == *old;
+    if rest_unchanged && old.definition != new.definition {
+        if let (Some(old_definition), Some(new_definition)) = (&old.definition, &new.definition) {
+            println!("  {} definition", "~".yellow());
+            print_text_diff(old_definition, new_definition);
+        }
+    }
+
+    print_owner_and_grant_changes(diff.owner_changed(), &old.owner, &new.owner, &old.grants, &new.grants);
+}
+
+fn print_owner_and_grant_changes(
+    owner_changed: bool,
+    old_owner: &Option<String>,
+    new_owner: &Option<String>,
+    old_grants: &[Grant],
+    new_grants: &[Grant],
+) {
+    if let (true, Some(old_owner), Some(new_owner)) = (owner_changed, old_owner, new_owner) {
+        println!("    owner: {} -> {}", old_owner.red(), new_owner.green());
+    }
+
+    let (added, removed) = diff_grants(old_grants, new_grants);
+    print_grant_changes("  ", &added, &removed);
+}
+
+fn print_sequence_diff(diff: &SequenceDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} sequence {}", "~".yellow().bold(), diff.sequence_name.yellow());
+
+    let settings = [
+        ("type", old.data_type.clone(), new.data_type.clone()),
+        ("start", old.start_value.to_string(), new.start_value.to_string()),
+        ("increment", old.increment.to_string(), new.increment.to_string()),
+        ("min", old.min_value.to_string(), new.min_value.to_string()),
+        ("max", old.max_value.to_string(), new.max_value.to_string()),
+        ("cycle", old.cycle.to_string(), new.cycle.to_string()),
+        ("owned by", sequence_owner(&old.owned_by), sequence_owner(&new.owned_by)),
+    ];
+
+    for (label, old_value, new_value) in settings {
+        if old_value != new_value {
+            println!("    {}: {} -> {}", label, old_value.red(), new_value.green());
+        }
+    }
+
+    print_owner_and_grant_changes(diff.owner_changed(), &old.owner, &new.owner, &old.grants, &new.grants);
+}
+
+/// One-line description of a sequence, e.g. `public.users_id_seq integer START 1 INCREMENT 1 OWNED BY public.users.id`
+fn describe_sequence(sequence: &Sequence) -> String {
+    let mut text = format!(
+        "{} {} START {} INCREMENT {}",
+        sequence.qualified_name(),
+        sequence.data_type,
+        sequence.start_value,
+        sequence.increment
+    );
+
+    if sequence.cycle {
+        text.push_str(" CYCLE");
+    }
+
+    if sequence.owned_by.is_some() {
+        text.push_str(&format!(" OWNED BY {}", sequence_owner(&sequence.owned_by)));
+    }
+
+    text
+}
+
+fn sequence_owner(owner: &Option<SequenceOwner>) -> String {
+    owner
+        .as_ref()
+        .map(SequenceOwner::qualified_name)
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+fn print_enum_diff(diff: &EnumDiff) {
+    println!("{} enum {}", "~".yellow().bold(), diff.type_name.yellow());
+
+    for label in &diff.labels_added {
+        println!("  {} label '{}'", "+".green(), label.green());
+    }
+
+    for label in &diff.labels_removed {
+        println!("  {} label '{}'", "-".red(), label.red());
+    }
+
+    if diff.labels_reordered {
+        println!(
+            "    order: {} -> {}",
+            diff.old.labels.join(", ").red(),
+            diff.new.labels.join(", ").green()
+        );
+    }
+}
+
+fn print_domain_diff(diff: &DomainDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} domain {}", "~".yellow().bold(), diff.type_name.yellow());
+
+    if old.base_type != new.base_type {
+        println!("    type: {} -> {}", old.base_type.red(), new.base_type.green());
+    }
+
+    if old.not_null != new.not_null {
+        let describe = |not_null: bool| if not_null { "NOT NULL" } else { "NULL" };
+        println!("    nullable: {} -> {}", describe(old.not_null).red(), describe(new.not_null).green());
+    }
+
+    if old.default_value != new.default_value {
+        let old_default = old.default_value.as_deref().unwrap_or("(none)");
+        let new_default = new.default_value.as_deref().unwrap_or("(none)");
+        println!("    default: {} -> {}", old_default.red(), new_default.green());
+    }
+
+    for constraint in &new.constraints {
+        match old.constraints.iter().find(|c| c.name == constraint.name) {
+            None => println!(
+                "  {} constraint {} {}",
+                "+".green(),
+                constraint.name.green(),
+                constraint.definition.green()
+            ),
+            Some(old_constraint) if old_constraint.definition != constraint.definition => {
+                println!("  {} constraint {}", "~".yellow(), constraint.name.yellow());
+                println!("    {}", old_constraint.definition.red());
+                println!("    {}", constraint.definition.green());
+            }
+            Some(_) => {}
+        }
+    }
+
+    for constraint in &old.constraints {
+        if !new.constraints.iter().any(|c| c.name == constraint.name) {
+            println!(
+                "  {} constraint {} {}",
+                "-".red(),
+                constraint.name.red(),
+                constraint.definition.red()
+            );
+        }
+    }
+}
+
+fn print_composite_type_diff(diff: &CompositeTypeDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} type {}", "~".yellow().bold(), diff.type_name.yellow());
+
+    for attribute in &new.attributes {
+        match old.attributes.iter().find(|a| a.name == attribute.name) {
+            None => println!("  {} {} ({})", "+".green(), attribute.name.green(), attribute.data_type),
+            Some(old_attribute) if old_attribute.data_type != attribute.data_type => {
+                println!("  {} {}", "~".yellow(), attribute.name.yellow());
+                println!(
+                    "    type: {} -> {}",
+                    old_attribute.data_type.red(),
+                    attribute.data_type.green()
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    for attribute in &old.attributes {
+        if !new.attributes.iter().any(|a| a.name == attribute.name) {
+            println!("  {} {} ({})", "-".red(), attribute.name.red(), attribute.data_type);
+        }
+    }
+}
+
+/// One-line description of an enum, e.g. `public.mood ('sad', 'ok', 'happy')`
+fn describe_enum(enum_type: &EnumType) -> String {
+    let labels: Vec<String> = enum_type.labels.iter().map(|l| format!("'{}'", l)).collect();
+    format!("{} ({})", enum_type.qualified_name(), labels.join(", "))
+}
+
+/// One-line description of a domain, e.g. `public.positive_int integer NOT NULL CHECK (VALUE > 0)`
+fn describe_domain(domain: &Domain) -> String {
+    let mut text = format!("{} {}", domain.qualified_name(), domain.base_type);
+
+    if let Some(default) = &domain.default_value {
+        text.push_str(&format!(" DEFAULT {}", default));
+    }
+
+    if domain.not_null {
+        text.push_str(" NOT NULL");
+    }
+
+    for constraint in &domain.constraints {
+        text.push_str(&format!(" {}", constraint.definition));
+    }
+
+    text
+}
+
+/// One-line description of a composite type, e.g. `public.address (street text, city text)`
+fn describe_composite_type(composite: &CompositeType) -> String {
+    let attributes: Vec<String> = composite
+        .attributes
+        .iter()
+        .map(|a| format!("{} {}", a.name, a.data_type))
+        .collect();
+    format!("{} ({})", composite.qualified_name(), attributes.join(", "))
+}
+
+fn routine_kind(routine: &Routine) -> &'static str {
+    match routine.kind {
+        RoutineKind::Function => "function",
+        RoutineKind::Procedure => "procedure",
+    }
+}
+
+/// Prints a line-based diff of two texts, indented under the object it belongs to
+fn print_text_diff(old: &str, new: &str) {
+    for change in diff_lines(old, new) {
+        match change {
+            LineChange::Unchanged(line) => println!("      {}", line.dimmed()),
+            LineChange::Added(line) => println!("    {} {}", "+".green(), line.green()),
+            LineChange::Removed(line) => println!("    {} {}", "-".red(), line.red()),
+        }
+    }
 }
 
 fn print_column_diff(diff: &ColumnDiff) {
     println!("  {} {}", "~".yellow(), diff.column_name.yellow());
 
-    if diff.old.data_type != diff.new.data_type {
+    if let Some(type_change) = diff.type_change {
+        let label = match type_change {
+            TypeChange::Length => "length",
+            TypeChange::Precision => "precision",
+            TypeChange::Type => "type",
+        };
         println!(
-            "    type: {} -> {}",
+            "    {}: {} -> {}",
+            label,
             diff.old.data_type.red(),
             diff.new.data_type.green()
         );
     }
 
+    if diff.collation_changed() {
+        let old_collation = collation_label(&diff.old);
+        let new_collation = collation_label(&diff.new);
+        println!("    collation: {} -> {}", old_collation.red(), new_collation.green());
+    }
+
     if diff.old.is_nullable != diff.new.is_nullable {
         let old_null = if diff.old.is_nullable { "NULL" } else { "NOT NULL" };
         let new_null = if diff.new.is_nullable { "NULL" } else { "NOT NULL" };
@@ -68,4 +846,411 @@ fn print_column_diff(diff: &ColumnDiff) {
         let new_default = diff.new.default_value.as_deref().unwrap_or("(none)");
         println!("    default: {} -> {}", old_default.red(), new_default.green());
     }
+
+    if diff.old.generated != diff.new.generated {
+        println!(
+            "    generated: {} -> {}",
+            describe_generated(&diff.old.generated).red(),
+            describe_generated(&diff.new.generated).green()
+        );
+    }
+
+    if diff.old.identity != diff.new.identity {
+        println!(
+            "    identity: {} -> {}",
+            describe_identity(&diff.old.identity).red(),
+            describe_identity(&diff.new.identity).green()
+        );
+    }
+
+    if diff.grants_changed() {
+        let (added, removed) = diff_grants(&diff.old.grants, &diff.new.grants);
+        print_grant_changes("    ", &added, &removed);
+    }
+}
+
+/// Collation of a column, schema-qualified when the schema is known
+fn collation_label(column: &Column) -> String {
+    match (&column.collation_schema, &column.collation) {
+        (Some(schema), Some(collation)) => format!("{}.{}", schema, collation),
+        (None, Some(collation)) => collation.clone(),
+        (_, None) => "(default)".to_string(),
+    }
+}
+
+/// Column type as shown next to its name, noting computed columns,
+/// e.g. `numeric, generated stored as (price * quantity)`
+fn describe_column_type(column: &Column) -> String {
+    match &column.generated {
+        Some(_) => format!("{}, {}", column.data_type, describe_generated(&column.generated)),
+        None => column.data_type.clone(),
+    }
+}
+
+fn describe_generated(generated: &Option<GeneratedColumn>) -> String {
+    match generated {
+        Some(generated) => {
+            let kind = match generated.kind {
+                GeneratedKind::Stored => "stored",
+                GeneratedKind::Virtual => "virtual",
+            };
+            format!("generated {} as ({})", kind, generated.expression)
+        }
+        None => "(none)".to_string(),
+    }
+}
+
+fn describe_identity(identity: &Option<Identity>) -> &'static str {
+    match identity {
+        Some(Identity::Always) => "GENERATED ALWAYS",
+        Some(Identity::ByDefault) => "GENERATED BY DEFAULT",
+        None => "(none)",
+    }
+}
+
+fn print_index_diff(diff: &IndexDiff) {
+    println!("  {} index {}", "~".yellow(), diff.index_name.yellow());
+    println!("    {}", describe_index(&diff.old).red());
+    println!("    {}", describe_index(&diff.new).green());
+}
+
+fn print_constraint_diff(diff: &ConstraintDiff) {
+    println!("  {} constraint {}", "~".yellow(), diff.constraint_name.yellow());
+    println!("    {}", describe_constraint(&diff.old).red());
+    println!("    {}", describe_constraint(&diff.new).green());
+}
+
+fn print_trigger_diff(diff: &TriggerDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("  {} trigger {}", "~".yellow(), diff.trigger_name.yellow());
+
+    // Enabled state is not part of the definition, so it is reported on its own
+    if old.enabled != new.enabled {
+        println!(
+            "    enabled: {} -> {}",
+            trigger_enabled(&old.enabled).red(),
+            trigger_enabled(&new.enabled).green()
+        );
+    }
+
+    if old.definition != new.definition {
+        println!("    {}", describe_trigger(old).red());
+        println!("    {}", describe_trigger(new).green());
+    }
+}
+
+fn print_event_trigger_diff(diff: &EventTriggerDiff) {
+    let (old, new) = (&diff.old, &diff.new);
+    println!("{} event trigger {}", "~".yellow().bold(), diff.trigger_name.yellow());
+
+    if old.enabled != new.enabled {
+        println!(
+            "    enabled: {} -> {}",
+            trigger_enabled(&old.enabled).red(),
+            trigger_enabled(&new.enabled).green()
+        );
+    }
+
+    if old.event != new.event || old.tags != new.tags || old.function != new.function {
+        println!("    {}", describe_event_trigger(old).red());
+        println!("    {}", describe_event_trigger(new).green());
+    }
+}
+
+/// One-line description of a policy, e.g. `own_rows FOR SELECT TO app USING ((owner = CURRENT_USER))`
+fn describe_policy(policy: &Policy) -> String {
+    let command = match policy.command {
+        PolicyCommand::All => "ALL",
+        PolicyCommand::Select => "SELECT",
+        PolicyCommand::Insert => "INSERT",
+        PolicyCommand::Update => "UPDATE",
+        PolicyCommand::Delete => "DELETE",
+    };
+    let mut text = policy.name.clone();
+
+    if !policy.permissive {
+        text.push_str(" AS RESTRICTIVE");
+    }
+    text.push_str(&format!(" FOR {} TO {}", command, policy.roles.join(", ")));
+
+    if let Some(using) = &policy.using {
+        text.push_str(&format!(" USING ({})", using));
+    }
+    if let Some(with_check) = &policy.with_check {
+        text.push_str(&format!(" WITH CHECK ({})", with_check));
+    }
+
+    text
+}
+
+/// One-line description of a trigger, e.g. `audit BEFORE UPDATE FOR EACH ROW -> public.audit()`
+fn describe_trigger(trigger: &Trigger) -> String {
+    let timing = match trigger.timing {
+        TriggerTiming::Before => "BEFORE",
+        TriggerTiming::After => "AFTER",
+        TriggerTiming::InsteadOf => "INSTEAD OF",
+    };
+    let level = if trigger.for_each_row { "ROW" } else { "STATEMENT" };
+    let mut text = format!(
+        "{} {} {} FOR EACH {}",
+        trigger.name,
+        timing,
+        trigger.events.join(" OR "),
+        level
+    );
+
+    if let Some(condition) = &trigger.condition {
+        text.push_str(&format!(" WHEN ({})", condition));
+    }
+
+    text.push_str(&format!(" -> {}()", trigger.function));
+
+    if trigger.enabled != TriggerEnabled::Origin {
+        text.push_str(&format!(" [{}]", trigger_enabled(&trigger.enabled)));
+    }
+
+    text
+}
+
+/// One-line description of an event trigger, e.g. `log_ddl ON ddl_command_end -> public.log_ddl()`
+fn describe_event_trigger(trigger: &EventTrigger) -> String {
+    let mut text = format!("{} ON {}", trigger.name, trigger.event);
+
+    if !trigger.tags.is_empty() {
+        text.push_str(&format!(" ({})", trigger.tags.join(", ")));
+    }
+
+    text.push_str(&format!(" -> {}()", trigger.function));
+
+    if trigger.enabled != TriggerEnabled::Origin {
+        text.push_str(&format!(" [{}]", trigger_enabled(&trigger.enabled)));
+    }
+
+    text
+}
+
+fn trigger_enabled(enabled: &TriggerEnabled) -> &'static str {
+    match enabled {
+        TriggerEnabled::Origin => "enabled",
+        TriggerEnabled::Disabled => "disabled",
+        TriggerEnabled::Replica => "replica only",
+        TriggerEnabled::Always => "always",
+    }
+}
+
+/// One-line description of a partition, e.g.
+/// `events_2024_03 FOR VALUES FROM ('2024-03-01') TO ('2024-04-01')`
+fn describe_partition(partition: &Table) -> String {
+    let bound = partition.partition_of.as_ref().map(|p| p.bound.as_str()).unwrap_or_default();
+    format!("{} {}{}", partition.name, bound, describe_partitioned(partition))
+}
+
+/// Suffix for partitioned tables, e.g. ` partitioned by RANGE (created_at)`
+fn describe_partitioned(table: &Table) -> String {
+    match &table.partitioning {
+        Some(partitioning) => format!(" partitioned by {}", describe_partitioning(Some(partitioning))),
+        None => String::new(),
+    }
+}
+
+fn describe_partitioning(partitioning: Option<&Partitioning>) -> String {
+    match partitioning {
+        Some(partitioning) => {
+            let strategy = match partitioning.strategy {
+                PartitionStrategy::Range => "RANGE",
+                PartitionStrategy::List => "LIST",
+                PartitionStrategy::Hash => "HASH",
+            };
+            format!("{} ({})", strategy, partitioning.key)
+        }
+        None => "not partitioned".to_string(),
+    }
+}
+
+fn describe_partition_of(partition_of: Option<&PartitionBound>) -> String {
+    match partition_of {
+        Some(partition_of) => format!("{} {}", partition_of.parent_name(), partition_of.bound),
+        None => "not a partition".to_string(),
+    }
+}
+
+/// One-line description of an index, e.g. `users_email_key UNIQUE (email)`
+fn describe_index(index: &Index) -> String {
+    let kind = if index.is_primary {
+        " PRIMARY"
+    } else if index.is_unique {
+        " UNIQUE"
+    } else {
+        ""
+    };
+    let mut text = format!("{}{}", index.name, kind);
+
+    if let Some(method) = index.method.as_deref().filter(|m| *m != "btree") {
+        text.push_str(&format!(" USING {}", method));
+    }
+
+    let keys: Vec<String> = index
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let mut key = column.clone();
+            if let Some(options) = index.key_options.get(i) {
+                if let Some(opclass) = &options.opclass {
+                    key.push_str(&format!(" {}", opclass));
+                }
+                if options.descending {
+                    key.push_str(" DESC");
+                }
+                if options.nulls_first != options.descending {
+                    key.push_str(if options.nulls_first { " NULLS FIRST" } else { " NULLS LAST" });
+                }
+            }
+            key
+        })
+        .collect();
+    text.push_str(&format!(" ({})", keys.join(", ")));
+
+    if !index.include.is_empty() {
+        text.push_str(&format!(" INCLUDE ({})", index.include.join(", ")));
+    }
+    if let Some(predicate) = &index.predicate {
+        text.push_str(&format!(" WHERE {}", predicate));
+    }
+    if !index.is_valid {
+        text.push_str(" INVALID");
+    }
+
+    text
+}
+
+/// One-line description of a constraint, e.g. `posts_user_id_fkey FOREIGN KEY (user_id) -> users (id)`
+fn describe_constraint(constraint: &Constraint) -> String {
+    let kind = match constraint.constraint_type {
+        ConstraintType::PrimaryKey => "PRIMARY KEY",
+        ConstraintType::ForeignKey => "FOREIGN KEY",
+        ConstraintType::Unique => "UNIQUE",
+        ConstraintType::Check => "CHECK",
+        ConstraintType::Exclusion => "EXCLUDE",
+        ConstraintType::NotNull => "NOT NULL",
+    };
+    let mut text = match &constraint.definition {
+        Some(definition) => format!("{} {}", constraint.name, definition),
+        None => format!("{} {} ({})", constraint.name, kind, constraint.columns.join(", ")),
+    };
+
+    if let Some(foreign_table) = &constraint.foreign_table {
+        let foreign_table = match &constraint.foreign_schema {
+            Some(schema) => format!("{}.{}", schema, foreign_table),
+            None => foreign_table.clone(),
+        };
+        let foreign_columns = constraint.foreign_columns.clone().unwrap_or_default();
+        text.push_str(&format!(" -> {} ({})", foreign_table, foreign_columns.join(", ")));
+    }
+
+    if let Some(match_type) = &constraint.match_type {
+        if *match_type != ForeignKeyMatch::Simple {
+            text.push_str(&format!(" MATCH {:?}", match_type).to_uppercase());
+        }
+    }
+
+    for (event, action) in [("UPDATE", &constraint.on_update), ("DELETE", &constraint.on_delete)] {
+        if let Some(action) = action {
+            if *action != ForeignKeyAction::NoAction {
+                text.push_str(&format!(" ON {} {}", event, foreign_key_action(action)));
+            }
+        }
+    }
+
+    if constraint.deferrable {
+        text.push_str(" DEFERRABLE");
+        if constraint.initially_deferred {
+            text.push_str(" INITIALLY DEFERRED");
+        }
+    }
+
+    if constraint.not_valid {
+        text.push_str(" NOT VALID");
+    }
+
+    text
+}
+
+fn foreign_key_action(action: &ForeignKeyAction) -> &'static str {
+    match action {
+        ForeignKeyAction::NoAction => "NO ACTION",
+        ForeignKeyAction::Restrict => "RESTRICT",
+        ForeignKeyAction::Cascade => "CASCADE",
+        ForeignKeyAction::SetNull => "SET NULL",
+        ForeignKeyAction::SetDefault => "SET DEFAULT",
+    }
+}
+
+/// Formats diff stats as a compact "+3 tables, ~5 columns, -1 index" summary
+pub fn format_stats(stats: &DiffStats) -> String {
+    let counts = [
+        ("+", stats.schemas_added, "schema", "schemas"),
+        ("-", stats.schemas_removed, "schema", "schemas"),
+        ("+", stats.tables_added, "table", "tables"),
+        ("-", stats.tables_removed, "table", "tables"),
+        ("~", stats.tables_modified, "table", "tables"),
+        ("+", stats.partitions_added, "partition", "partitions"),
+        ("-", stats.partitions_removed, "partition", "partitions"),
+        ("+", stats.sequences_added, "sequence", "sequences"),
+        ("-", stats.sequences_removed, "sequence", "sequences"),
+        ("~", stats.sequences_modified, "sequence", "sequences"),
+        ("+", stats.types_added, "type", "types"),
+        ("-", stats.types_removed, "type", "types"),
+        ("~", stats.types_modified, "type", "types"),
+        ("+", stats.columns_added, "column", "columns"),
+        ("-", stats.columns_removed, "column", "columns"),
+        ("~", stats.columns_modified, "column", "columns"),
+        ("+", stats.indexes_added, "index", "indexes"),
+        ("-", stats.indexes_removed, "index", "indexes"),
+        ("~", stats.indexes_modified, "index", "indexes"),
+        ("+", stats.constraints_added, "constraint", "constraints"),
+        ("-", stats.constraints_removed, "constraint", "constraints"),
+        ("~", stats.constraints_modified, "constraint", "constraints"),
+        ("+", stats.triggers_added, "trigger", "triggers"),
+        ("-", stats.triggers_removed, "trigger", "triggers"),
+        ("~", stats.triggers_modified, "trigger", "triggers"),
+        ("+", stats.policies_added, "policy", "policies"),
+        ("-", stats.policies_removed, "policy", "policies"),
+        ("~", stats.policies_modified, "policy", "policies"),
+        ("+", stats.views_added, "view", "views"),
+        ("-", stats.views_removed, "view", "views"),
+        ("~", stats.views_modified, "view", "views"),
+        ("+", stats.materialized_views_added, "materialized view", "materialized views"),
+        ("-", stats.materialized_views_removed, "materialized view", "materialized views"),
+        ("~", stats.materialized_views_modified, "materialized view", "materialized views"),
+        ("+", stats.routines_added, "routine", "routines"),
+        ("-", stats.routines_removed, "routine", "routines"),
+        ("~", stats.routines_modified, "routine", "routines"),
+        ("+", stats.event_triggers_added, "event trigger", "event triggers"),
+        ("-", stats.event_triggers_removed, "event trigger", "event triggers"),
+        ("~", stats.event_triggers_modified, "event trigger", "event triggers"),
+        ("~", stats.owners_changed, "owner", "owners"),
+        ("+", stats.grants_added, "grant", "grants"),
+        ("-", stats.grants_removed, "grant", "grants"),
+    ];
+
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(_, count, _, _)| *count > 0)
+        .map(|(sign, count, singular, plural)| {
+            let noun = if *count == 1 { singular } else { plural };
+            let text = format!("{}{} {}", sign, count, noun);
+            match *sign {
+                "+" => text.green().to_string(),
+                "-" => text.red().to_string(),
+                _ => text.yellow().to_string(),
+            }
+        })
+        .collect();
+
+    if parts.is_empty() {
+        "no changes".dimmed().to_string()
+    } else {
+        parts.join(", ")
+    }
 }
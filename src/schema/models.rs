@@ -4,10 +4,147 @@ use chrono::{DateTime, Utc};
 /// Represents a complete database schema snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schema {
+    /// Names of user schemas (namespaces), including empty ones
+    #[serde(default)]
+    pub schemas: Vec<String>,
     pub tables: Vec<Table>,
+    #[serde(default)]
+    pub sequences: Vec<Sequence>,
+    #[serde(default)]
+    pub enums: Vec<EnumType>,
+    #[serde(default)]
+    pub domains: Vec<Domain>,
+    #[serde(default)]
+    pub composite_types: Vec<CompositeType>,
+    #[serde(default)]
+    pub views: Vec<View>,
+    #[serde(default)]
+    pub materialized_views: Vec<MaterializedView>,
+    #[serde(default)]
+    pub routines: Vec<Routine>,
+    #[serde(default)]
+    pub event_triggers: Vec<EventTrigger>,
+    /// Owner and grants of each schema in `schemas`
+    #[serde(default)]
+    pub schema_privileges: Vec<SchemaPrivileges>,
+    /// Which of the `format` versions this snapshot was captured with
+    #[serde(default)]
+    pub format: u32,
     pub captured_at: DateTime<Utc>,
 }
 
+/// Snapshot format versions.
+///
+/// Each version records something that snapshots taken before it lack. A
+/// missing field reads back as empty, which would otherwise look like a
+/// change, so the diff treats anything newer than a snapshot's format as
+/// unknown for that snapshot.
+pub mod format {
+    use serde_json::Value;
+
+    /// Tables, columns, indexes and constraints only
+    pub const TABLES: u32 = 0;
+    /// Views and materialized views
+    pub const VIEWS: u32 = 1;
+    /// Functions and procedures
+    pub const ROUTINES: u32 = 2;
+    /// Table triggers and event triggers
+    pub const TRIGGERS: u32 = 3;
+    /// Sequences and identity columns
+    pub const SEQUENCES: u32 = 4;
+    /// Enums, domains and composite types
+    pub const TYPES: u32 = 5;
+    /// Column types with length, precision and array dimensions, and collations
+    pub const COLUMN_TYPES: u32 = 6;
+    /// Generated column expressions, stored or virtual
+    pub const GENERATED_COLUMNS: u32 = 7;
+    /// Foreign key actions, match type and deferrability, and ordered key columns
+    pub const FOREIGN_KEYS: u32 = 8;
+    /// Check, exclusion and NOT NULL constraints
+    pub const CONSTRAINT_DEFINITIONS: u32 = 9;
+    /// Index method, key expressions and options, INCLUDE, predicate and validity
+    pub const INDEXES: u32 = 10;
+    /// Partitioning of tables, and the parent and bound of each partition
+    pub const PARTITIONS: u32 = 11;
+    /// Row-level security flags and policies
+    pub const ROW_SECURITY: u32 = 12;
+    /// Owners and grants
+    pub const PRIVILEGES: u32 = 13;
+    /// Which indexes belong to a constraint
+    pub const CONSTRAINT_INDEXES: u32 = 14;
+    /// Routine attributes, `BEGIN ATOMIC` bodies and full definitions
+    pub const ROUTINE_ATTRIBUTES: u32 = 15;
+    /// Owners and grants of views and materialized views
+    pub const VIEW_PRIVILEGES: u32 = 16;
+    /// Schemas of column collations
+    pub const COLLATION_SCHEMAS: u32 = 17;
+
+    /// Format written by this version
+    pub const CURRENT: u32 = COLLATION_SCHEMAS;
+
+    /// Format of a serialized schema. Snapshots written before the format
+    /// was recorded are placed by the newest field they contain.
+    pub fn of(schema: &Value) -> u32 {
+        if let Some(format) = schema.get("format").and_then(Value::as_u64) {
+            return format as u32;
+        }
+
+        let has = |key: &str| schema.get(key).is_some();
+        let tables: Vec<&Value> = items(schema, "tables").collect();
+        let tables_have = |key: &str| tables.iter().any(|t| t.get(key).is_some());
+        let nested_have = |list: &str, key: &str| {
+            tables
+                .iter()
+                .flat_map(|t| items(t, list))
+                .any(|item| item.get(key).is_some())
+        };
+
+        let markers = [
+            (PRIVILEGES, has("schema_privileges")),
+            (ROW_SECURITY, tables_have("policies")),
+            (PARTITIONS, tables_have("partitioning")),
+            (INDEXES, nested_have("indexes", "method")),
+            (CONSTRAINT_DEFINITIONS, nested_have("constraints", "definition")),
+            (FOREIGN_KEYS, nested_have("constraints", "on_delete")),
+            (GENERATED_COLUMNS, nested_have("columns", "generated")),
+            (COLUMN_TYPES, nested_have("columns", "collation")),
+            (TYPES, has("enums")),
+            (SEQUENCES, has("sequences")),
+            (TRIGGERS, has("event_triggers")),
+            (ROUTINES, has("routines")),
+            (VIEWS, has("views")),
+        ];
+        markers
+            .iter()
+            .find(|(_, present)| *present)
+            .map_or(TABLES, |(format, _)| *format)
+    }
+
+    fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+        value.get(key).and_then(Value::as_array).into_iter().flatten()
+    }
+}
+
+/// Owner and grants of a schema
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaPrivileges {
+    pub name: String,
+    pub owner: String,
+    pub grants: Vec<Grant>,
+}
+
+/// One privilege held by one role on an object. Privileges the owner holds
+/// on its own object are left out.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Grant {
+    /// Role name, or `public` for every role
+    pub grantee: String,
+    /// e.g. `SELECT`, `USAGE` or `EXECUTE`
+    pub privilege: String,
+    /// Granted `WITH GRANT OPTION`
+    pub grantable: bool,
+}
+
 /// Represents a database table
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Table {
@@ -16,25 +153,386 @@ pub struct Table {
     pub columns: Vec<Column>,
     pub indexes: Vec<Index>,
     pub constraints: Vec<Constraint>,
+    #[serde(default)]
+    pub triggers: Vec<Trigger>,
+    /// How a partitioned table splits its rows
+    #[serde(default)]
+    pub partitioning: Option<Partitioning>,
+    /// Parent and bound of a partition. Inherited indexes and constraints
+    /// are left out, as they are created along with the partition.
+    #[serde(default)]
+    pub partition_of: Option<PartitionBound>,
+    /// Row-level security is enabled
+    #[serde(default)]
+    pub rls_enabled: bool,
+    /// Row-level security also applies to the table owner
+    #[serde(default)]
+    pub rls_forced: bool,
+    #[serde(default)]
+    pub policies: Vec<Policy>,
+    /// None in snapshots taken before owners were recorded
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+/// Represents a row-level security policy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Policy {
+    pub name: String,
+    pub command: PolicyCommand,
+    /// Permissive policies are OR-ed together; restrictive ones must all pass
+    pub permissive: bool,
+    /// Roles the policy applies to, sorted; `public` for everyone
+    pub roles: Vec<String>,
+    /// USING expression, checked against existing rows
+    pub using: Option<String>,
+    /// WITH CHECK expression, checked against new rows
+    pub with_check: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PolicyCommand {
+    All,
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Partitioning {
+    pub strategy: PartitionStrategy,
+    /// Partition key columns and expressions, e.g. `created_at`
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PartitionStrategy {
+    Range,
+    List,
+    Hash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartitionBound {
+    pub parent_schema: String,
+    pub parent_table: String,
+    /// e.g. `FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')` or `DEFAULT`
+    pub bound: String,
+}
+
+/// Represents a table trigger
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trigger {
+    pub name: String,
+    pub timing: TriggerTiming,
+    /// INSERT, UPDATE, DELETE and/or TRUNCATE
+    pub events: Vec<String>,
+    pub for_each_row: bool,
+    /// WHEN condition, if any
+    pub condition: Option<String>,
+    /// Schema-qualified name of the trigger function
+    pub function: String,
+    pub enabled: TriggerEnabled,
+    /// Full CREATE TRIGGER statement with a schema-qualified table, from `pg_get_triggerdef`
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TriggerTiming {
+    Before,
+    After,
+    InsteadOf,
+}
+
+/// When a trigger fires, following `session_replication_role`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TriggerEnabled {
+    /// Fires in origin and local modes (the default)
+    Origin,
+    Disabled,
+    /// Fires only in replica mode
+    Replica,
+    /// Fires in every mode
+    Always,
+}
+
+/// Represents a database-level event trigger
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventTrigger {
+    pub name: String,
+    /// e.g. `ddl_command_start`
+    pub event: String,
+    /// Command tags the trigger is limited to, empty for all
+    pub tags: Vec<String>,
+    /// Schema-qualified name of the trigger function
+    pub function: String,
+    pub enabled: TriggerEnabled,
+}
+
+/// Represents a standalone or serial sequence. Identity sequences are
+/// described by their column instead.
+///
+/// The current value is deliberately not captured: it changes with every
+/// insert, and snapshots should only change when the schema does.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Sequence {
+    pub name: String,
+    pub schema: String,
+    /// `smallint`, `integer` or `bigint`
+    pub data_type: String,
+    pub start_value: i64,
+    pub increment: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub cycle: bool,
+    /// Column the sequence is dropped with, set by `OWNED BY`
+    pub owned_by: Option<SequenceOwner>,
+    /// Role owning the sequence; None in snapshots taken before owners were recorded
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SequenceOwner {
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+}
+
+/// Represents an enum type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnumType {
+    pub name: String,
+    pub schema: String,
+    /// Labels in sort order
+    pub labels: Vec<String>,
+}
+
+/// Represents a domain over a base type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Domain {
+    pub name: String,
+    pub schema: String,
+    pub base_type: String,
+    pub default_value: Option<String>,
+    pub not_null: bool,
+    pub constraints: Vec<DomainConstraint>,
+}
+
+/// A CHECK constraint on a domain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DomainConstraint {
+    pub name: String,
+    /// e.g. `CHECK (VALUE > 0)`, as returned by `pg_get_constraintdef`
+    pub definition: String,
+}
+
+/// Represents a standalone composite type (`CREATE TYPE ... AS (...)`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompositeType {
+    pub name: String,
+    pub schema: String,
+    pub attributes: Vec<CompositeAttribute>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompositeAttribute {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// Represents a view
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub schema: String,
+    /// Normalized query text, as returned by `pg_get_viewdef`
+    pub definition: String,
+    pub columns: Vec<Column>,
+    /// None in snapshots taken before view owners were recorded
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+/// Represents a materialized view
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaterializedView {
+    pub name: String,
+    pub schema: String,
+    /// Normalized query text, as returned by `pg_get_viewdef`
+    pub definition: String,
+    pub columns: Vec<Column>,
+    pub indexes: Vec<Index>,
+    /// None in snapshots taken before view owners were recorded
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+/// Represents a function or procedure
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Routine {
+    pub name: String,
+    pub schema: String,
+    pub kind: RoutineKind,
+    /// Argument types only, e.g. `integer, text`; identifies overloads
+    pub argument_types: String,
+    /// Full argument list with names, modes and defaults
+    pub arguments: String,
+    /// None for procedures
+    pub return_type: Option<String>,
+    pub language: String,
+    pub volatility: Volatility,
+    pub security_definer: bool,
+    /// Source text, or the parsed SQL of a `BEGIN ATOMIC` / `RETURN` body
+    pub body: String,
+    /// `STRICT`: returns null on null input without being called
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub leakproof: bool,
+    /// None for procedures and in snapshots taken before it was recorded
+    #[serde(default)]
+    pub parallel: Option<ParallelSafety>,
+    /// Planner cost estimate, in units of `cpu_operator_cost`
+    #[serde(default)]
+    pub cost: Option<f32>,
+    /// Planner estimate of rows returned, set only for set-returning functions
+    #[serde(default)]
+    pub rows: Option<f32>,
+    /// `SET` clauses as `name=value`, e.g. `search_path=public, pg_temp`
+    #[serde(default)]
+    pub config: Vec<String>,
+    /// Complete `CREATE OR REPLACE` statement from `pg_get_functiondef`,
+    /// which recreates the routine with everything it was defined with
+    #[serde(default)]
+    pub definition: Option<String>,
+    /// None in snapshots taken before owners were recorded
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Includes the `EXECUTE` that `public` holds unless revoked
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RoutineKind {
+    Function,
+    Procedure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Volatility {
+    Immutable,
+    Stable,
+    Volatile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ParallelSafety {
+    Safe,
+    Restricted,
+    Unsafe,
 }
 
 /// Represents a table column
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Column {
     pub name: String,
+    /// Full type as written in DDL, e.g. `character varying(255)` or `numeric(12,4)[]`
     pub data_type: String,
     pub is_nullable: bool,
     pub default_value: Option<String>,
     pub ordinal_position: i32,
+    /// Set for `GENERATED ... AS IDENTITY` columns
+    #[serde(default)]
+    pub identity: Option<Identity>,
+    /// Set only when it differs from the type's default collation
+    #[serde(default)]
+    pub collation: Option<String>,
+    /// Schema of the collation, e.g. `pg_catalog`
+    #[serde(default)]
+    pub collation_schema: Option<String>,
+    /// Set for `GENERATED ALWAYS AS (...)` computed columns
+    #[serde(default)]
+    pub generated: Option<GeneratedColumn>,
+    /// Column-level grants, on top of any on the whole table
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeneratedColumn {
+    pub kind: GeneratedKind,
+    /// Generation expression, as returned by `pg_get_expr`
+    pub expression: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum GeneratedKind {
+    /// Computed on write and stored
+    Stored,
+    /// Computed on read (PostgreSQL 18 and later)
+    Virtual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Identity {
+    /// `GENERATED ALWAYS AS IDENTITY`
+    Always,
+    /// `GENERATED BY DEFAULT AS IDENTITY`
+    ByDefault,
 }
 
 /// Represents a table index
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Index {
     pub name: String,
+    /// Key columns in order; expression keys hold their expression text
     pub columns: Vec<String>,
     pub is_unique: bool,
     pub is_primary: bool,
+    /// Access method, e.g. `btree` or `gin`
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Per-key options, paired by position with `columns`
+    #[serde(default)]
+    pub key_options: Vec<IndexKeyOptions>,
+    /// Non-key columns from the INCLUDE clause
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// WHERE clause of a partial index
+    #[serde(default)]
+    pub predicate: Option<String>,
+    /// False when a failed `CREATE INDEX CONCURRENTLY` left the index unusable
+    #[serde(default = "default_index_valid")]
+    pub is_valid: bool,
+    /// Created by the primary key, unique or exclusion constraint of the
+    /// same name, and only dropped along with it
+    #[serde(default)]
+    pub backs_constraint: bool,
+}
+
+fn default_index_valid() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexKeyOptions {
+    /// The key is an expression rather than a plain column
+    pub expression: bool,
+    pub descending: bool,
+    pub nulls_first: bool,
+    /// Operator class, when not the default for the key's type
+    pub opclass: Option<String>,
 }
 
 /// Represents a table constraint
@@ -42,9 +540,47 @@ pub struct Index {
 pub struct Constraint {
     pub name: String,
     pub constraint_type: ConstraintType,
+    /// Key columns in declaration order
     pub columns: Vec<String>,
+    #[serde(default)]
+    pub foreign_schema: Option<String>,
     pub foreign_table: Option<String>,
+    /// Referenced columns, paired by position with `columns`
     pub foreign_columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub on_update: Option<ForeignKeyAction>,
+    #[serde(default)]
+    pub on_delete: Option<ForeignKeyAction>,
+    #[serde(default)]
+    pub match_type: Option<ForeignKeyMatch>,
+    #[serde(default)]
+    pub deferrable: bool,
+    #[serde(default)]
+    pub initially_deferred: bool,
+    /// Added with `NOT VALID` and not validated since
+    #[serde(default)]
+    pub not_valid: bool,
+    /// Body of a check, exclusion or NOT NULL constraint as
+    /// `pg_get_constraintdef` prints it, e.g. `CHECK ((qty > 0))`, without
+    /// the DEFERRABLE / NOT VALID trailer recorded above
+    #[serde(default)]
+    pub definition: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ForeignKeyAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ForeignKeyMatch {
+    Simple,
+    Full,
+    Partial,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -53,26 +589,169 @@ pub enum ConstraintType {
     ForeignKey,
     Unique,
     Check,
+    Exclusion,
+    /// Named NOT NULL constraint of PostgreSQL 18, found in older snapshots.
+    /// The diff goes by column nullability instead.
+    NotNull,
 }
 
 impl Schema {
     pub fn new() -> Self {
         Self {
+            schemas: Vec::new(),
             tables: Vec::new(),
+            sequences: Vec::new(),
+            enums: Vec::new(),
+            domains: Vec::new(),
+            composite_types: Vec::new(),
+            views: Vec::new(),
+            materialized_views: Vec::new(),
+            routines: Vec::new(),
+            event_triggers: Vec::new(),
+            schema_privileges: Vec::new(),
+            format: format::CURRENT,
             captured_at: Utc::now(),
         }
     }
 
     pub fn with_tables(tables: Vec<Table>) -> Self {
         Self {
+            schemas: Vec::new(),
             tables,
+            sequences: Vec::new(),
+            enums: Vec::new(),
+            domains: Vec::new(),
+            composite_types: Vec::new(),
+            views: Vec::new(),
+            materialized_views: Vec::new(),
+            routines: Vec::new(),
+            event_triggers: Vec::new(),
+            schema_privileges: Vec::new(),
+            format: format::CURRENT,
             captured_at: Utc::now(),
         }
     }
 
+    /// Parse a serialized schema, working out its format if it predates
+    /// the `format` field
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let format = format::of(&value);
+        let mut schema: Schema = serde_json::from_value(value)?;
+        schema.format = format;
+        schema.upgrade();
+        Ok(schema)
+    }
+
+    /// Work out what older formats left out but the rest of the snapshot
+    /// still tells
+    pub fn upgrade(&mut self) {
+        // Indexes share the name of the constraint they belong to
+        if self.format < format::CONSTRAINT_INDEXES {
+            for table in &mut self.tables {
+                for index in &mut table.indexes {
+                    index.backs_constraint = table.constraints.iter().any(|c| {
+                        c.name == index.name
+                            && matches!(
+                                c.constraint_type,
+                                ConstraintType::PrimaryKey | ConstraintType::Unique | ConstraintType::Exclusion
+                            )
+                    });
+                }
+            }
+        }
+    }
+
     pub fn table_count(&self) -> usize {
         self.tables.len()
     }
+
+    /// Schema names in this snapshot. Snapshots taken before schemas were
+    /// recorded fall back to the schemas that contain tables.
+    pub fn schema_names(&self) -> Vec<String> {
+        if !self.schemas.is_empty() {
+            return self.schemas.clone();
+        }
+
+        let mut names: Vec<String> = self.tables.iter().map(|t| t.schema.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+impl Table {
+    /// Schema-qualified name, e.g. `public.users`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+
+    pub fn is_partition(&self) -> bool {
+        self.partition_of.is_some()
+    }
+}
+
+impl PartitionBound {
+    /// Schema-qualified name of the parent table
+    pub fn parent_name(&self) -> String {
+        format!("{}.{}", self.parent_schema, self.parent_table)
+    }
+}
+
+impl Sequence {
+    /// Schema-qualified name, e.g. `public.invoice_number_seq`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+impl SequenceOwner {
+    /// Fully qualified column, e.g. `public.users.id`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}.{}", self.schema, self.table, self.column)
+    }
+}
+
+impl EnumType {
+    /// Schema-qualified name, e.g. `public.order_status`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+impl Domain {
+    /// Schema-qualified name, e.g. `public.email_address`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+impl CompositeType {
+    /// Schema-qualified name, e.g. `public.address`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+impl Routine {
+    /// Full signature that distinguishes overloads, e.g. `public.add(integer, integer)`
+    pub fn signature(&self) -> String {
+        format!("{}.{}({})", self.schema, self.name, self.argument_types)
+    }
+}
+
+impl View {
+    /// Schema-qualified name, e.g. `public.active_users`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
+}
+
+impl MaterializedView {
+    /// Schema-qualified name, e.g. `public.daily_totals`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema, self.name)
+    }
 }
 
 impl Default for Schema {
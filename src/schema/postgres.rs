@@ -1,188 +1,1338 @@
-use anyhow::Result;
-use sqlx::postgres::PgPool;
-use sqlx::Row;
+use anyhow::{Context, Result};
+use sqlx::postgres::{PgConnection, PgRow};
+use sqlx::{Connection, Row};
+use std::collections::HashMap;
 
-use super::models::{Column, Constraint, ConstraintType, Index, Schema, Table};
+use super::models::{
+    Column, CompositeAttribute, CompositeType, Constraint, ConstraintType, Domain, DomainConstraint,
+    EnumType, EventTrigger, ForeignKeyAction, ForeignKeyMatch, GeneratedColumn, GeneratedKind, Grant,
+    Identity, Index, IndexKeyOptions, MaterializedView, ParallelSafety, PartitionBound, PartitionStrategy,
+    Partitioning, Policy, PolicyCommand, Routine, RoutineKind, Schema, SchemaPrivileges, Sequence,
+    SequenceOwner, Table, Trigger, TriggerEnabled, TriggerTiming, View, Volatility,
+};
+use crate::config::Config;
 
-/// Extracts schema from a PostgreSQL database
-pub async fn extract_schema(connection_string: &str) -> Result<Schema> {
-    let pool = PgPool::connect(connection_string).await?;
+/// Extracts schema from a PostgreSQL database.
+///
+/// All catalog queries run on one connection inside a single
+/// `REPEATABLE READ, READ ONLY` transaction, so the snapshot reflects one
+/// point in time even if DDL runs concurrently. The configured statement and
+/// lock timeouts keep extraction from queueing behind (and blocking) DDL.
+pub async fn extract_schema(config: &Config) -> Result<Schema> {
+    let mut conn = PgConnection::connect(&config.connection_string).await?;
 
-    let tables = extract_tables(&pool).await?;
+    let mut tx = conn
+        .begin_with("BEGIN ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .await
+        .context("Failed to start extraction transaction")?;
 
-    Ok(Schema::with_tables(tables))
+    sqlx::raw_sql(&format!(
+        "SET LOCAL statement_timeout = {}; SET LOCAL lock_timeout = {};",
+        config.statement_timeout_ms, config.lock_timeout_ms
+    ))
+    .execute(&mut *tx)
+    .await
+    .context("Failed to set extraction timeouts")?;
+
+    let server_version = server_version(&mut tx).await?;
+
+    let schemas = extract_schemas(&mut tx).await?;
+    let schema_privileges = extract_schema_privileges(&mut tx).await?;
+    let tables = extract_tables(&mut tx, server_version).await?;
+    let sequences = extract_sequences(&mut tx).await?;
+    let enums = extract_enums(&mut tx).await?;
+    let domains = extract_domains(&mut tx).await?;
+    let composite_types = extract_composite_types(&mut tx).await?;
+    let views = extract_views(&mut tx).await?;
+    let materialized_views = extract_materialized_views(&mut tx).await?;
+    let routines = extract_routines(&mut tx, server_version).await?;
+    let event_triggers = extract_event_triggers(&mut tx).await?;
+
+    tx.rollback().await?;
+
+    Ok(Schema {
+        schemas,
+        sequences,
+        enums,
+        domains,
+        composite_types,
+        views,
+        materialized_views,
+        routines,
+        event_triggers,
+        schema_privileges,
+        ..Schema::with_tables(tables)
+    })
+}
+
+/// The server's `server_version_num`, e.g. 150004 for 15.4
+async fn server_version(conn: &mut PgConnection) -> Result<i32> {
+    sqlx::query_scalar("SELECT current_setting('server_version_num')::int4")
+        .fetch_one(&mut *conn)
+        .await
+        .context("Failed to read server version")
+}
+
+/// Get all user schemas (excluding system schemas)
+async fn extract_schemas(conn: &mut PgConnection) -> Result<Vec<String>> {
+    let schemas = sqlx::query_scalar(
+        r#"
+        SELECT nspname::text
+        FROM pg_namespace
+        WHERE nspname NOT IN ('pg_catalog', 'information_schema')
+          AND nspname NOT LIKE 'pg\_toast%'
+          AND nspname NOT LIKE 'pg\_temp\_%'
+        ORDER BY nspname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(schemas)
+}
+
+/// Get the owner and grants of every schema returned by `extract_schemas`
+async fn extract_schema_privileges(conn: &mut PgConnection) -> Result<Vec<SchemaPrivileges>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            nspname::text AS schema_name,
+            pg_get_userbyid(nspowner)::text AS owner,
+            coalesce(nspacl, acldefault('n', nspowner))::text[] AS acl
+        FROM pg_namespace
+        WHERE nspname NOT IN ('pg_catalog', 'information_schema')
+          AND nspname NOT LIKE 'pg\_toast%'
+          AND nspname NOT LIKE 'pg\_temp\_%'
+        ORDER BY nspname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let privileges = rows
+        .iter()
+        .map(|row| {
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            SchemaPrivileges {
+                name: row.get("schema_name"),
+                grants: parse_acl(&acl, &owner),
+                owner,
+            }
+        })
+        .collect();
+
+    Ok(privileges)
 }
 
-/// Get all user tables (excluding system schemas)
-async fn extract_tables(pool: &PgPool) -> Result<Vec<Table>> {
+/// Get all user tables (excluding system schemas).
+///
+/// Columns, indexes and constraints are fetched for every table at once with
+/// set-based catalog queries and assembled in memory, so the number of round
+/// trips does not grow with the number of tables.
+async fn extract_tables(conn: &mut PgConnection, server_version: i32) -> Result<Vec<Table>> {
     let rows = sqlx::query(
         r#"
-        SELECT table_schema, table_name
-        FROM information_schema.tables
-        WHERE table_type = 'BASE TABLE'
-          AND table_schema NOT IN ('pg_catalog', 'information_schema')
-        ORDER BY table_schema, table_name
+        SELECT
+            t.table_schema::text,
+            t.table_name::text,
+            pt.partstrat::text AS partition_strategy,
+            substring(pg_get_partkeydef(c.oid) FROM '^\w+ \((.*)\)$') AS partition_key,
+            pn.nspname::text AS parent_schema,
+            parent.relname::text AS parent_table,
+            pg_get_expr(c.relpartbound, c.oid) AS partition_bound,
+            c.relrowsecurity AS rls_enabled,
+            c.relforcerowsecurity AS rls_forced,
+            pg_get_userbyid(c.relowner)::text AS owner,
+            coalesce(c.relacl, acldefault('r', c.relowner))::text[] AS acl
+        FROM information_schema.tables t
+        JOIN pg_namespace n ON n.nspname = t.table_schema
+        JOIN pg_class c ON c.relnamespace = n.oid AND c.relname = t.table_name
+        LEFT JOIN pg_partitioned_table pt ON pt.partrelid = c.oid
+        LEFT JOIN pg_inherits i ON i.inhrelid = c.oid AND c.relispartition
+        LEFT JOIN pg_class parent ON parent.oid = i.inhparent
+        LEFT JOIN pg_namespace pn ON pn.oid = parent.relnamespace
+        WHERE t.table_type = 'BASE TABLE'
+          AND t.table_schema NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY t.table_schema, t.table_name
         "#,
     )
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await?;
 
-    let mut tables = Vec::new();
+    let mut tables: Vec<Table> = rows
+        .iter()
+        .map(|row| {
+            let strategy: Option<String> = row.get("partition_strategy");
+            let key: Option<String> = row.get("partition_key");
+            let parent_schema: Option<String> = row.get("parent_schema");
+            let parent_table: Option<String> = row.get("parent_table");
+            let bound: Option<String> = row.get("partition_bound");
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            Table {
+                name: row.get("table_name"),
+                schema: row.get("table_schema"),
+                columns: Vec::new(),
+                indexes: Vec::new(),
+                constraints: Vec::new(),
+                triggers: Vec::new(),
+                partitioning: match (strategy.as_deref(), key) {
+                    (Some(strategy), Some(key)) => Some(Partitioning {
+                        strategy: match strategy {
+                            "l" => PartitionStrategy::List,
+                            "h" => PartitionStrategy::Hash,
+                            _ => PartitionStrategy::Range,
+                        },
+                        key,
+                    }),
+                    _ => None,
+                },
+                partition_of: match (parent_schema, parent_table, bound) {
+                    (Some(parent_schema), Some(parent_table), Some(bound)) => Some(PartitionBound {
+                        parent_schema,
+                        parent_table,
+                        bound,
+                    }),
+                    _ => None,
+                },
+                rls_enabled: row.get("rls_enabled"),
+                rls_forced: row.get("rls_forced"),
+                policies: Vec::new(),
+                grants: parse_acl(&acl, &owner),
+                owner: Some(owner),
+            }
+        })
+        .collect();
+
+    let positions: HashMap<TableKey, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, t)| ((t.schema.clone(), t.name.clone()), i))
+        .collect();
+
+    for (key, column) in extract_columns(conn, &["r", "p"]).await? {
+        if let Some(&i) = positions.get(&key) {
+            tables[i].columns.push(column);
+        }
+    }
+
+    for (key, index) in extract_indexes(conn, &["r", "p"]).await? {
+        if let Some(&i) = positions.get(&key) {
+            tables[i].indexes.push(index);
+        }
+    }
 
-    for row in rows {
-        let schema: String = row.get("table_schema");
-        let name: String = row.get("table_name");
+    for (key, constraint) in extract_constraints(conn).await? {
+        if let Some(&i) = positions.get(&key) {
+            tables[i].constraints.push(constraint);
+        }
+    }
 
-        let columns = extract_columns(pool, &schema, &name).await?;
-        let indexes = extract_indexes(pool, &schema, &name).await?;
-        let constraints = extract_constraints(pool, &schema, &name).await?;
+    for (key, trigger) in extract_triggers(conn, server_version).await? {
+        if let Some(&i) = positions.get(&key) {
+            tables[i].triggers.push(trigger);
+        }
+    }
 
-        tables.push(Table {
-            name,
-            schema,
-            columns,
-            indexes,
-            constraints,
-        });
+    for (key, policy) in extract_policies(conn).await? {
+        if let Some(&i) = positions.get(&key) {
+            tables[i].policies.push(policy);
+        }
     }
 
     Ok(tables)
 }
 
-/// Get all columns for a table
-async fn extract_columns(pool: &PgPool, schema: &str, table: &str) -> Result<Vec<Column>> {
+/// Get all sequences (excluding system schemas).
+///
+/// Sequences backing identity columns are skipped, since they are created
+/// and dropped with their column, as are sequences owned by extensions.
+async fn extract_sequences(conn: &mut PgConnection) -> Result<Vec<Sequence>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS sequence_schema,
+            c.relname::text AS sequence_name,
+            format_type(s.seqtypid, NULL) AS data_type,
+            s.seqstart AS start_value,
+            s.seqincrement AS increment,
+            s.seqmin AS min_value,
+            s.seqmax AS max_value,
+            s.seqcycle AS cycle,
+            owner_ns.nspname::text AS owner_schema,
+            owner.relname::text AS owner_table,
+            owner_col.attname::text AS owner_column,
+            pg_get_userbyid(c.relowner)::text AS owner,
+            coalesce(c.relacl, acldefault('s', c.relowner))::text[] AS acl
+        FROM pg_sequence s
+        JOIN pg_class c ON c.oid = s.seqrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_depend d
+            ON d.classid = 'pg_class'::regclass
+            AND d.objid = c.oid
+            AND d.refclassid = 'pg_class'::regclass
+            AND d.deptype = 'a'
+        LEFT JOIN pg_class owner ON owner.oid = d.refobjid
+        LEFT JOIN pg_namespace owner_ns ON owner_ns.oid = owner.relnamespace
+        LEFT JOIN pg_attribute owner_col
+            ON owner_col.attrelid = d.refobjid
+            AND owner_col.attnum = d.refobjsubid
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_depend x
+              WHERE x.classid = 'pg_class'::regclass
+                AND x.objid = c.oid
+                AND x.deptype IN ('i', 'e')
+          )
+        ORDER BY n.nspname, c.relname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let sequences = rows
+        .iter()
+        .map(|row| {
+            let owner_schema: Option<String> = row.get("owner_schema");
+            let owner_table: Option<String> = row.get("owner_table");
+            let owner_column: Option<String> = row.get("owner_column");
+            let owned_by = match (owner_schema, owner_table, owner_column) {
+                (Some(schema), Some(table), Some(column)) => Some(SequenceOwner { schema, table, column }),
+                _ => None,
+            };
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            Sequence {
+                name: row.get("sequence_name"),
+                schema: row.get("sequence_schema"),
+                data_type: row.get("data_type"),
+                start_value: row.get("start_value"),
+                increment: row.get("increment"),
+                min_value: row.get("min_value"),
+                max_value: row.get("max_value"),
+                cycle: row.get("cycle"),
+                owned_by,
+                grants: parse_acl(&acl, &owner),
+                owner: Some(owner),
+            }
+        })
+        .collect();
+
+    Ok(sequences)
+}
+
+/// Get all enum types with their labels in sort order, excluding those owned by extensions
+async fn extract_enums(conn: &mut PgConnection) -> Result<Vec<EnumType>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS type_schema,
+            t.typname::text AS type_name,
+            ARRAY(
+                SELECT e.enumlabel::text
+                FROM pg_enum e
+                WHERE e.enumtypid = t.oid
+                ORDER BY e.enumsortorder
+            ) AS labels
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE t.typtype = 'e'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_depend d
+              WHERE d.classid = 'pg_type'::regclass
+                AND d.objid = t.oid
+                AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, t.typname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let enums = rows
+        .iter()
+        .map(|row| EnumType {
+            name: row.get("type_name"),
+            schema: row.get("type_schema"),
+            labels: row.get("labels"),
+        })
+        .collect();
+
+    Ok(enums)
+}
+
+/// Get all domains with their constraints, excluding those owned by extensions
+async fn extract_domains(conn: &mut PgConnection) -> Result<Vec<Domain>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS type_schema,
+            t.typname::text AS type_name,
+            format_type(t.typbasetype, t.typtypmod) AS base_type,
+            t.typdefault AS default_value,
+            t.typnotnull AS not_null
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE t.typtype = 'd'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_depend d
+              WHERE d.classid = 'pg_type'::regclass
+                AND d.objid = t.oid
+                AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, t.typname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut domains: Vec<Domain> = rows
+        .iter()
+        .map(|row| Domain {
+            name: row.get("type_name"),
+            schema: row.get("type_schema"),
+            base_type: row.get("base_type"),
+            default_value: row.get("default_value"),
+            not_null: row.get("not_null"),
+            constraints: Vec::new(),
+        })
+        .collect();
+
+    let positions: HashMap<TableKey, usize> = domains
+        .iter()
+        .enumerate()
+        .map(|(i, d)| ((d.schema.clone(), d.name.clone()), i))
+        .collect();
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            t.typname::text AS table_name,
+            c.conname::text AS constraint_name,
+            pg_get_constraintdef(c.oid, true) AS definition
+        FROM pg_constraint c
+        JOIN pg_type t ON t.oid = c.contypid
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE c.contype = 'c'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, t.typname, c.conname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in &rows {
+        if let Some(&i) = positions.get(&table_key(row)) {
+            domains[i].constraints.push(DomainConstraint {
+                name: row.get("constraint_name"),
+                definition: row.get("definition"),
+            });
+        }
+    }
+
+    Ok(domains)
+}
+
+/// Get all standalone composite types with their attributes, excluding those
+/// owned by extensions. The row types of tables and views are not included.
+async fn extract_composite_types(conn: &mut PgConnection) -> Result<Vec<CompositeType>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            t.typname::text AS table_name,
+            a.attname::text AS attribute_name,
+            format_type(a.atttypid, a.atttypmod) AS data_type
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        JOIN pg_class c ON c.oid = t.typrelid
+        LEFT JOIN pg_attribute a
+            ON a.attrelid = c.oid
+            AND a.attnum > 0
+            AND NOT a.attisdropped
+        WHERE t.typtype = 'c'
+          AND c.relkind = 'c'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_depend d
+              WHERE d.classid = 'pg_type'::regclass
+                AND d.objid = t.oid
+                AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, t.typname, a.attnum
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut types: Vec<CompositeType> = Vec::new();
+    for row in &rows {
+        let (schema, name) = table_key(row);
+        if !types.last().is_some_and(|t| t.schema == schema && t.name == name) {
+            types.push(CompositeType {
+                name,
+                schema,
+                attributes: Vec::new(),
+            });
+        }
+
+        let attribute_name: Option<String> = row.get("attribute_name");
+        if let (Some(attribute_name), Some(current)) = (attribute_name, types.last_mut()) {
+            current.attributes.push(CompositeAttribute {
+                name: attribute_name,
+                data_type: row.get("data_type"),
+            });
+        }
+    }
+
+    Ok(types)
+}
+
+/// Get all views (excluding system schemas) with their columns, owner and grants
+async fn extract_views(conn: &mut PgConnection) -> Result<Vec<View>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            c.relname::text AS table_name,
+            pg_get_viewdef(c.oid, true) AS definition,
+            pg_get_userbyid(c.relowner)::text AS owner,
+            coalesce(c.relacl, acldefault('r', c.relowner))::text[] AS acl
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind = 'v'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, c.relname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut views: Vec<View> = rows
+        .iter()
+        .map(|row| {
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            View {
+                name: row.get("table_name"),
+                schema: row.get("table_schema"),
+                definition: normalize_definition(row.get("definition")),
+                columns: Vec::new(),
+                grants: parse_acl(&acl, &owner),
+                owner: Some(owner),
+            }
+        })
+        .collect();
+
+    let positions: HashMap<TableKey, usize> = views
+        .iter()
+        .enumerate()
+        .map(|(i, v)| ((v.schema.clone(), v.name.clone()), i))
+        .collect();
+
+    for (key, column) in extract_columns(conn, &["v"]).await? {
+        if let Some(&i) = positions.get(&key) {
+            views[i].columns.push(column);
+        }
+    }
+
+    Ok(views)
+}
+
+/// Get all materialized views (excluding system schemas) with their columns, indexes,
+/// owner and grants
+async fn extract_materialized_views(conn: &mut PgConnection) -> Result<Vec<MaterializedView>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            c.relname::text AS table_name,
+            pg_get_viewdef(c.oid, true) AS definition,
+            pg_get_userbyid(c.relowner)::text AS owner,
+            coalesce(c.relacl, acldefault('r', c.relowner))::text[] AS acl
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind = 'm'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, c.relname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut views: Vec<MaterializedView> = rows
+        .iter()
+        .map(|row| {
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            MaterializedView {
+                name: row.get("table_name"),
+                schema: row.get("table_schema"),
+                definition: normalize_definition(row.get("definition")),
+                columns: Vec::new(),
+                indexes: Vec::new(),
+                grants: parse_acl(&acl, &owner),
+                owner: Some(owner),
+            }
+        })
+        .collect();
+
+    let positions: HashMap<TableKey, usize> = views
+        .iter()
+        .enumerate()
+        .map(|(i, v)| ((v.schema.clone(), v.name.clone()), i))
+        .collect();
+
+    for (key, column) in extract_columns(conn, &["m"]).await? {
+        if let Some(&i) = positions.get(&key) {
+            views[i].columns.push(column);
+        }
+    }
+
+    for (key, index) in extract_indexes(conn, &["m"]).await? {
+        if let Some(&i) = positions.get(&key) {
+            views[i].indexes.push(index);
+        }
+    }
+
+    Ok(views)
+}
+
+/// Get all user functions and procedures, excluding those owned by extensions.
+///
+/// `BEGIN ATOMIC` and `RETURN` bodies are stored parsed rather than in
+/// `prosrc`, so they are printed back with `pg_get_function_sqlbody`. Both
+/// arrived in PostgreSQL 14; older servers only have `prosrc`.
+async fn extract_routines(conn: &mut PgConnection, server_version: i32) -> Result<Vec<Routine>> {
+    let body = if server_version >= 140000 {
+        "coalesce(pg_get_function_sqlbody(p.oid), p.prosrc)"
+    } else {
+        "p.prosrc"
+    };
+
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname::text AS routine_schema,
+            p.proname::text AS routine_name,
+            p.prokind::text AS kind,
+            oidvectortypes(p.proargtypes) AS argument_types,
+            pg_get_function_arguments(p.oid) AS arguments,
+            CASE WHEN p.prokind = 'p' THEN NULL ELSE pg_get_function_result(p.oid) END AS return_type,
+            l.lanname::text AS language,
+            p.provolatile::text AS volatility,
+            p.prosecdef AS security_definer,
+            {body} AS body,
+            p.proisstrict AS strict,
+            p.proleakproof AS leakproof,
+            p.proparallel::text AS parallel,
+            p.procost AS cost,
+            p.prorows AS rows,
+            coalesce(p.proconfig, '{{}}') AS config,
+            pg_get_functiondef(p.oid) AS definition,
+            pg_get_userbyid(p.proowner)::text AS owner,
+            coalesce(p.proacl, acldefault('f', p.proowner))::text[] AS acl
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        JOIN pg_language l ON l.oid = p.prolang
+        WHERE p.prokind IN ('f', 'p')
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND n.nspname NOT LIKE 'pg\_toast%'
+          AND n.nspname NOT LIKE 'pg\_temp\_%'
+          AND NOT EXISTS (
+              SELECT 1
+              FROM pg_depend d
+              WHERE d.classid = 'pg_proc'::regclass
+                AND d.objid = p.oid
+                AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, p.proname, argument_types
+        "#
+    );
+    let rows = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+    let routines = rows
+        .iter()
+        .map(|row| {
+            let kind: String = row.get("kind");
+            let volatility: String = row.get("volatility");
+            let body: String = row.get("body");
+            let parallel: String = row.get("parallel");
+            let rows: f32 = row.get("rows");
+            let definition: String = row.get("definition");
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            Routine {
+                name: row.get("routine_name"),
+                schema: row.get("routine_schema"),
+                kind: match kind.as_str() {
+                    "p" => RoutineKind::Procedure,
+                    _ => RoutineKind::Function,
+                },
+                argument_types: row.get("argument_types"),
+                arguments: row.get("arguments"),
+                return_type: row.get("return_type"),
+                language: row.get("language"),
+                volatility: match volatility.as_str() {
+                    "i" => Volatility::Immutable,
+                    "s" => Volatility::Stable,
+                    _ => Volatility::Volatile,
+                },
+                security_definer: row.get("security_definer"),
+                body: body.trim().to_string(),
+                strict: row.get("strict"),
+                leakproof: row.get("leakproof"),
+                parallel: match (kind.as_str(), parallel.as_str()) {
+                    ("p", _) => None,
+                    (_, "s") => Some(ParallelSafety::Safe),
+                    (_, "r") => Some(ParallelSafety::Restricted),
+                    _ => Some(ParallelSafety::Unsafe),
+                },
+                cost: Some(row.get("cost")),
+                rows: (rows > 0.0).then_some(rows),
+                config: row.get("config"),
+                definition: Some(definition.trim_end().to_string()),
+                grants: parse_acl(&acl, &owner),
+                owner: Some(owner),
+            }
+        })
+        .collect();
+
+    Ok(routines)
+}
+
+/// Get all columns of all relations of the given `pg_class.relkind`s.
+///
+/// Types come from `format_type`, so they keep their length, precision and
+/// array dimensions (`character varying(255)`, `numeric(12,4)`, `text[]`).
+/// Collation is only recorded when it differs from the type's default.
+/// Generated columns keep their expression apart from the default.
+async fn extract_columns(conn: &mut PgConnection, relkinds: &[&str]) -> Result<Vec<(TableKey, Column)>> {
     let rows = sqlx::query(
         r#"
         SELECT
-            column_name,
-            data_type,
-            is_nullable,
-            column_default,
-            ordinal_position
-        FROM information_schema.columns
-        WHERE table_schema = $1 AND table_name = $2
-        ORDER BY ordinal_position
+            n.nspname::text AS table_schema,
+            c.relname::text AS table_name,
+            a.attname::text AS column_name,
+            format_type(a.atttypid, a.atttypmod) AS data_type,
+            NOT (a.attnotnull OR (t.typtype = 'd' AND t.typnotnull)) AS is_nullable,
+            CASE WHEN a.attgenerated = '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS column_default,
+            CASE WHEN a.attgenerated <> '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS generation_expression,
+            a.attgenerated::text AS generated_kind,
+            a.attnum::int4 AS ordinal_position,
+            a.attidentity::text AS identity,
+            CASE WHEN a.attcollation <> t.typcollation THEN co.collname::text END AS collation,
+            CASE WHEN a.attcollation <> t.typcollation THEN cn.nspname::text END AS collation_schema,
+            pg_get_userbyid(c.relowner)::text AS owner,
+            coalesce(a.attacl, '{}')::text[] AS acl
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_type t ON t.oid = a.atttypid
+        LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+        LEFT JOIN pg_collation co ON co.oid = a.attcollation
+        LEFT JOIN pg_namespace cn ON cn.oid = co.collnamespace
+        WHERE c.relkind::text = ANY($1)
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, c.relname, a.attnum
         "#,
     )
-    .bind(schema)
-    .bind(table)
-    .fetch_all(pool)
+    .bind(relkinds)
+    .fetch_all(&mut *conn)
     .await?;
 
     let columns = rows
         .iter()
         .map(|row| {
-            let is_nullable: String = row.get("is_nullable");
-            Column {
+            let identity: String = row.get("identity");
+            let generated_kind: String = row.get("generated_kind");
+            let generation_expression: Option<String> = row.get("generation_expression");
+            let owner: String = row.get("owner");
+            let acl: Vec<String> = row.get("acl");
+            let column = Column {
                 name: row.get("column_name"),
                 data_type: row.get("data_type"),
-                is_nullable: is_nullable == "YES",
+                is_nullable: row.get("is_nullable"),
                 default_value: row.get("column_default"),
                 ordinal_position: row.get("ordinal_position"),
-            }
+                identity: match identity.as_str() {
+                    "a" => Some(Identity::Always),
+                    "d" => Some(Identity::ByDefault),
+                    _ => None,
+                },
+                collation: row.get("collation"),
+                collation_schema: row.get("collation_schema"),
+                generated: generation_expression.map(|expression| GeneratedColumn {
+                    kind: match generated_kind.as_str() {
+                        "v" => GeneratedKind::Virtual,
+                        _ => GeneratedKind::Stored,
+                    },
+                    expression,
+                }),
+                grants: parse_acl(&acl, &owner),
+            };
+            (table_key(row), column)
         })
         .collect();
 
     Ok(columns)
 }
 
-/// Get all indexes for a table
-async fn extract_indexes(pool: &PgPool, schema: &str, table: &str) -> Result<Vec<Index>> {
+/// Get all indexes on relations of the given `pg_class.relkind`s.
+///
+/// Keys are walked by position rather than joined on `indkey`, so expression
+/// keys (which have no column) keep their place, and INCLUDE columns are
+/// split off after the `indnkeyatts` key columns.
+///
+/// Indexes of partitions that are attached to an index on the parent are
+/// skipped, as they are created along with the partition.
+async fn extract_indexes(conn: &mut PgConnection, relkinds: &[&str]) -> Result<Vec<(TableKey, Index)>> {
     let rows = sqlx::query(
         r#"
         SELECT
-            i.relname AS index_name,
-            array_agg(a.attname ORDER BY array_position(ix.indkey, a.attnum)) AS columns,
+            n.nspname::text AS table_schema,
+            t.relname::text AS table_name,
+            i.relname::text AS index_name,
+            ARRAY(
+                SELECT CASE WHEN ix.indkey[k - 1] = 0
+                    THEN pg_get_indexdef(ix.indexrelid, k, true)
+                    ELSE a.attname::text
+                END
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                LEFT JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = ix.indkey[k - 1]
+                ORDER BY k
+            ) AS columns,
+            ARRAY(
+                SELECT ix.indkey[k - 1] = 0
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                ORDER BY k
+            ) AS expressions,
+            ARRAY(
+                SELECT ix.indoption[k - 1] & 1 <> 0
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                ORDER BY k
+            ) AS descending,
+            ARRAY(
+                SELECT ix.indoption[k - 1] & 2 <> 0
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                ORDER BY k
+            ) AS nulls_first,
+            ARRAY(
+                SELECT CASE WHEN NOT opc.opcdefault THEN format('%I.%I', ocn.nspname, opc.opcname) END
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                JOIN pg_opclass opc ON opc.oid = ix.indclass[k - 1]
+                JOIN pg_namespace ocn ON ocn.oid = opc.opcnamespace
+                ORDER BY k
+            ) AS opclasses,
+            ARRAY(
+                SELECT a.attname::text
+                FROM generate_series(ix.indnkeyatts + 1, ix.indnatts) AS k
+                JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = ix.indkey[k - 1]
+                ORDER BY k
+            ) AS include,
+            am.amname::text AS method,
+            pg_get_expr(ix.indpred, ix.indrelid, true) AS predicate,
             ix.indisunique AS is_unique,
-            ix.indisprimary AS is_primary
+            ix.indisprimary AS is_primary,
+            ix.indisvalid AS is_valid,
+            EXISTS (
+                SELECT 1
+                FROM pg_constraint c
+                WHERE c.conrelid = ix.indrelid
+                  AND c.conindid = ix.indexrelid
+                  AND c.contype IN ('p', 'u', 'x')
+            ) AS backs_constraint
         FROM pg_class t
         JOIN pg_index ix ON t.oid = ix.indrelid
         JOIN pg_class i ON i.oid = ix.indexrelid
+        JOIN pg_am am ON am.oid = i.relam
         JOIN pg_namespace n ON n.oid = t.relnamespace
-        JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
-        WHERE n.nspname = $1
-          AND t.relname = $2
-        GROUP BY i.relname, ix.indisunique, ix.indisprimary
-        ORDER BY i.relname
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND t.relkind::text = ANY($1)
+          AND NOT EXISTS (SELECT 1 FROM pg_inherits inh WHERE inh.inhrelid = ix.indexrelid)
+        ORDER BY n.nspname, t.relname, i.relname
         "#,
     )
-    .bind(schema)
-    .bind(table)
-    .fetch_all(pool)
+    .bind(relkinds)
+    .fetch_all(&mut *conn)
     .await?;
 
     let indexes = rows
         .iter()
-        .map(|row| Index {
-            name: row.get("index_name"),
-            columns: row.get("columns"),
-            is_unique: row.get("is_unique"),
-            is_primary: row.get("is_primary"),
+        .map(|row| {
+            let expressions: Vec<bool> = row.get("expressions");
+            let descending: Vec<bool> = row.get("descending");
+            let nulls_first: Vec<bool> = row.get("nulls_first");
+            let opclasses: Vec<Option<String>> = row.get("opclasses");
+            let key_options = expressions
+                .into_iter()
+                .zip(descending)
+                .zip(nulls_first)
+                .zip(opclasses)
+                .map(|(((expression, descending), nulls_first), opclass)| IndexKeyOptions {
+                    expression,
+                    descending,
+                    nulls_first,
+                    opclass,
+                })
+                .collect();
+
+            let index = Index {
+                name: row.get("index_name"),
+                columns: row.get("columns"),
+                is_unique: row.get("is_unique"),
+                is_primary: row.get("is_primary"),
+                method: row.get("method"),
+                key_options,
+                include: row.get("include"),
+                predicate: row.get("predicate"),
+                is_valid: row.get("is_valid"),
+                backs_constraint: row.get("backs_constraint"),
+            };
+            (table_key(row), index)
         })
         .collect();
 
     Ok(indexes)
 }
 
-/// Get all constraints for a table
-async fn extract_constraints(pool: &PgPool, schema: &str, table: &str) -> Result<Vec<Constraint>> {
+/// Get all constraints of all user tables.
+///
+/// Key columns keep their declared order, so the columns of a composite
+/// foreign key stay paired with the columns they reference. Check and
+/// exclusion constraints keep their printed definition instead; the
+/// trailing DEFERRABLE / NOT VALID clauses are stripped since they are
+/// captured separately.
+///
+/// NOT NULL constraints, which PostgreSQL 18 lists here too, are skipped:
+/// every version reports them as column nullability, so snapshots of the
+/// same schema match across versions.
+///
+/// Constraints a partition inherits from its parent are skipped, as they are
+/// created along with the partition.
+async fn extract_constraints(conn: &mut PgConnection) -> Result<Vec<(TableKey, Constraint)>> {
     let rows = sqlx::query(
         r#"
         SELECT
-            tc.constraint_name,
-            tc.constraint_type,
-            array_agg(DISTINCT kcu.column_name) AS columns,
-            ccu.table_name AS foreign_table,
-            array_agg(DISTINCT ccu.column_name) FILTER (WHERE tc.constraint_type = 'FOREIGN KEY') AS foreign_columns
-        FROM information_schema.table_constraints tc
-        JOIN information_schema.key_column_usage kcu
-            ON tc.constraint_name = kcu.constraint_name
-            AND tc.table_schema = kcu.table_schema
-        LEFT JOIN information_schema.constraint_column_usage ccu
-            ON tc.constraint_name = ccu.constraint_name
-            AND tc.table_schema = ccu.table_schema
-        WHERE tc.table_schema = $1
-          AND tc.table_name = $2
-          AND tc.constraint_type IN ('PRIMARY KEY', 'FOREIGN KEY', 'UNIQUE', 'CHECK')
-        GROUP BY tc.constraint_name, tc.constraint_type, ccu.table_name
-        ORDER BY tc.constraint_name
+            n.nspname::text AS table_schema,
+            t.relname::text AS table_name,
+            c.conname::text AS constraint_name,
+            c.contype::text AS constraint_type,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS columns,
+            fn.nspname::text AS foreign_schema,
+            ft.relname::text AS foreign_table,
+            CASE WHEN c.contype = 'f' THEN ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.confkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) END AS foreign_columns,
+            c.confupdtype::text AS on_update,
+            c.confdeltype::text AS on_delete,
+            c.confmatchtype::text AS match_type,
+            c.condeferrable AS deferrable,
+            c.condeferred AS initially_deferred,
+            NOT c.convalidated AS not_valid,
+            CASE WHEN c.contype IN ('c', 'x') THEN regexp_replace(
+                pg_get_constraintdef(c.oid),
+                '( DEFERRABLE| INITIALLY DEFERRED| NOT VALID)+$',
+                ''
+            ) END AS definition
+        FROM pg_constraint c
+        JOIN pg_class t ON t.oid = c.conrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        LEFT JOIN pg_class ft ON ft.oid = c.confrelid
+        LEFT JOIN pg_namespace fn ON fn.oid = ft.relnamespace
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND c.contype IN ('p', 'f', 'u', 'c', 'x')
+          AND c.conparentid = 0
+          AND (c.conislocal OR NOT t.relispartition)
+        ORDER BY n.nspname, t.relname, c.conname
         "#,
     )
-    .bind(schema)
-    .bind(table)
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await?;
 
     let constraints = rows
         .iter()
         .map(|row| {
             let constraint_type: String = row.get("constraint_type");
-            let foreign_table: Option<String> = row.get("foreign_table");
-            let foreign_columns: Option<Vec<String>> = row.get("foreign_columns");
-
-            Constraint {
+            let on_update: String = row.get("on_update");
+            let on_delete: String = row.get("on_delete");
+            let match_type: String = row.get("match_type");
+            let constraint = Constraint {
                 name: row.get("constraint_name"),
                 constraint_type: match constraint_type.as_str() {
-                    "PRIMARY KEY" => ConstraintType::PrimaryKey,
-                    "FOREIGN KEY" => ConstraintType::ForeignKey,
-                    "UNIQUE" => ConstraintType::Unique,
-                    "CHECK" => ConstraintType::Check,
+                    "p" => ConstraintType::PrimaryKey,
+                    "f" => ConstraintType::ForeignKey,
+                    "u" => ConstraintType::Unique,
+                    "x" => ConstraintType::Exclusion,
                     _ => ConstraintType::Check,
                 },
                 columns: row.get("columns"),
-                foreign_table: if constraint_type == "FOREIGN KEY" {
-                    foreign_table
-                } else {
-                    None
+                foreign_schema: row.get("foreign_schema"),
+                foreign_table: row.get("foreign_table"),
+                foreign_columns: row.get("foreign_columns"),
+                on_update: parse_foreign_key_action(&on_update),
+                on_delete: parse_foreign_key_action(&on_delete),
+                match_type: match match_type.as_str() {
+                    "f" => Some(ForeignKeyMatch::Full),
+                    "p" => Some(ForeignKeyMatch::Partial),
+                    "s" => Some(ForeignKeyMatch::Simple),
+                    _ => None,
+                },
+                deferrable: row.get("deferrable"),
+                initially_deferred: row.get("initially_deferred"),
+                not_valid: row.get("not_valid"),
+                definition: row.get("definition"),
+            };
+            (table_key(row), constraint)
+        })
+        .collect();
+
+    Ok(constraints)
+}
+
+/// Map `confupdtype` / `confdeltype` codes to the model; blank for non-foreign keys
+fn parse_foreign_key_action(code: &str) -> Option<ForeignKeyAction> {
+    match code {
+        "a" => Some(ForeignKeyAction::NoAction),
+        "r" => Some(ForeignKeyAction::Restrict),
+        "c" => Some(ForeignKeyAction::Cascade),
+        "n" => Some(ForeignKeyAction::SetNull),
+        "d" => Some(ForeignKeyAction::SetDefault),
+        _ => None,
+    }
+}
+
+/// Get all row-level security policies of all tables
+async fn extract_policies(conn: &mut PgConnection) -> Result<Vec<(TableKey, Policy)>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            c.relname::text AS table_name,
+            p.polname::text AS policy_name,
+            p.polcmd::text AS command,
+            p.polpermissive AS permissive,
+            ARRAY(
+                SELECT CASE WHEN r = 0 THEN 'public' ELSE r::regrole::text END
+                FROM unnest(p.polroles) AS r
+                ORDER BY 1
+            ) AS roles,
+            pg_get_expr(p.polqual, p.polrelid) AS using_expression,
+            pg_get_expr(p.polwithcheck, p.polrelid) AS with_check_expression
+        FROM pg_policy p
+        JOIN pg_class c ON c.oid = p.polrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, c.relname, p.polname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let policies = rows
+        .iter()
+        .map(|row| {
+            let command: String = row.get("command");
+            let policy = Policy {
+                name: row.get("policy_name"),
+                command: match command.as_str() {
+                    "r" => PolicyCommand::Select,
+                    "a" => PolicyCommand::Insert,
+                    "w" => PolicyCommand::Update,
+                    "d" => PolicyCommand::Delete,
+                    _ => PolicyCommand::All,
                 },
-                foreign_columns: if constraint_type == "FOREIGN KEY" {
-                    foreign_columns
-                } else {
-                    None
+                permissive: row.get("permissive"),
+                roles: row.get("roles"),
+                using: row.get("using_expression"),
+                with_check: row.get("with_check_expression"),
+            };
+            (table_key(row), policy)
+        })
+        .collect();
+
+    Ok(policies)
+}
+
+/// Get all user-defined triggers of all tables.
+///
+/// Internal triggers (such as those implementing foreign keys) and clones of
+/// a partitioned table's trigger on its partitions are skipped. Clones point
+/// at their parent trigger through `tgparentid` from PostgreSQL 13; before
+/// that they were marked internal.
+async fn extract_triggers(conn: &mut PgConnection, server_version: i32) -> Result<Vec<(TableKey, Trigger)>> {
+    let not_cloned = if server_version >= 130000 {
+        "AND t.tgparentid = 0"
+    } else {
+        ""
+    };
+
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname::text AS table_schema,
+            c.relname::text AS table_name,
+            t.tgname::text AS trigger_name,
+            CASE
+                WHEN t.tgtype & 2 <> 0 THEN 'BEFORE'
+                WHEN t.tgtype & 64 <> 0 THEN 'INSTEAD OF'
+                ELSE 'AFTER'
+            END AS timing,
+            array_remove(ARRAY[
+                CASE WHEN t.tgtype & 4 <> 0 THEN 'INSERT' END,
+                CASE WHEN t.tgtype & 16 <> 0 THEN 'UPDATE' END,
+                CASE WHEN t.tgtype & 8 <> 0 THEN 'DELETE' END,
+                CASE WHEN t.tgtype & 32 <> 0 THEN 'TRUNCATE' END
+            ], NULL) AS events,
+            t.tgtype & 1 <> 0 AS for_each_row,
+            substring(pg_get_triggerdef(t.oid, true) FROM 'WHEN \((.*)\) EXECUTE') AS condition,
+            format('%I.%I', pn.nspname, p.proname) AS function,
+            t.tgenabled::text AS enabled,
+            pg_get_triggerdef(t.oid) AS definition
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_proc p ON p.oid = t.tgfoid
+        JOIN pg_namespace pn ON pn.oid = p.pronamespace
+        WHERE NOT t.tgisinternal
+          {not_cloned}
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY n.nspname, c.relname, t.tgname
+        "#
+    );
+    let rows = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+    let triggers = rows
+        .iter()
+        .map(|row| {
+            let timing: String = row.get("timing");
+            let enabled: String = row.get("enabled");
+            let trigger = Trigger {
+                name: row.get("trigger_name"),
+                timing: match timing.as_str() {
+                    "BEFORE" => TriggerTiming::Before,
+                    "INSTEAD OF" => TriggerTiming::InsteadOf,
+                    _ => TriggerTiming::After,
                 },
+                events: row.get("events"),
+                for_each_row: row.get("for_each_row"),
+                condition: row.get("condition"),
+                function: row.get("function"),
+                enabled: parse_trigger_enabled(&enabled),
+                definition: row.get("definition"),
+            };
+            (table_key(row), trigger)
+        })
+        .collect();
+
+    Ok(triggers)
+}
+
+/// Get all database-level event triggers, excluding those owned by extensions
+async fn extract_event_triggers(conn: &mut PgConnection) -> Result<Vec<EventTrigger>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            e.evtname::text AS trigger_name,
+            e.evtevent::text AS event,
+            COALESCE(e.evttags, '{}') AS tags,
+            format('%I.%I', pn.nspname, p.proname) AS function,
+            e.evtenabled::text AS enabled
+        FROM pg_event_trigger e
+        JOIN pg_proc p ON p.oid = e.evtfoid
+        JOIN pg_namespace pn ON pn.oid = p.pronamespace
+        WHERE NOT EXISTS (
+            SELECT 1
+            FROM pg_depend d
+            WHERE d.classid = 'pg_event_trigger'::regclass
+              AND d.objid = e.oid
+              AND d.deptype = 'e'
+        )
+        ORDER BY e.evtname
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let event_triggers = rows
+        .iter()
+        .map(|row| {
+            let enabled: String = row.get("enabled");
+            EventTrigger {
+                name: row.get("trigger_name"),
+                event: row.get("event"),
+                tags: row.get("tags"),
+                function: row.get("function"),
+                enabled: parse_trigger_enabled(&enabled),
             }
         })
         .collect();
 
-    Ok(constraints)
+    Ok(event_triggers)
+}
+
+/// Map `tgenabled` / `evtenabled` codes to the model
+fn parse_trigger_enabled(code: &str) -> TriggerEnabled {
+    match code {
+        "D" => TriggerEnabled::Disabled,
+        "R" => TriggerEnabled::Replica,
+        "A" => TriggerEnabled::Always,
+        _ => TriggerEnabled::Origin,
+    }
+}
+
+/// Trim a view definition so formatting-only noise does not show up as a change
+fn normalize_definition(definition: &str) -> String {
+    definition
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .trim_end_matches(';')
+        .to_string()
+}
+
+/// Expand `aclitem` entries (`grantee=privileges/grantor`) into one grant per
+/// privilege, leaving out what `owner` holds on its own object. An empty
+/// grantee stands for `public`.
+fn parse_acl(acl: &[String], owner: &str) -> Vec<Grant> {
+    let mut grants = Vec::new();
+    for item in acl {
+        let (grantee, rest) = split_acl_role(item);
+        if grantee == owner {
+            continue;
+        }
+        let grantee = if grantee.is_empty() { "public".to_string() } else { grantee };
+        let privileges = rest.trim_start_matches('=').split('/').next().unwrap_or("");
+        let mut codes = privileges.chars().peekable();
+        while let Some(code) = codes.next() {
+            grants.push(Grant {
+                grantee: grantee.clone(),
+                privilege: acl_privilege(code),
+                grantable: codes.next_if_eq(&'*').is_some(),
+            });
+        }
+    }
+    grants.sort_by(|a, b| (&a.grantee, &a.privilege).cmp(&(&b.grantee, &b.privilege)));
+    grants
+}
+
+/// Split the (possibly double-quoted) role name off the front of an `aclitem`
+fn split_acl_role(item: &str) -> (String, &str) {
+    let Some(quoted) = item.strip_prefix('"') else {
+        let end = item.find('=').unwrap_or(item.len());
+        return (item[..end].to_string(), &item[end..]);
+    };
+    let mut name = String::new();
+    let mut chars = quoted.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '"' {
+            name.push(c);
+        } else if chars.next_if(|&(_, c)| c == '"').is_some() {
+            name.push('"');
+        } else {
+            return (name, &quoted[i + 1..]);
+        }
+    }
+    (name, "")
+}
+
+/// Map an `aclitem` privilege letter to its SQL keyword
+fn acl_privilege(code: char) -> String {
+    let privilege = match code {
+        'r' => "SELECT",
+        'a' => "INSERT",
+        'w' => "UPDATE",
+        'd' => "DELETE",
+        'D' => "TRUNCATE",
+        'x' => "REFERENCES",
+        't' => "TRIGGER",
+        'm' => "MAINTAIN",
+        'X' => "EXECUTE",
+        'U' => "USAGE",
+        'C' => "CREATE",
+        'c' => "CONNECT",
+        'T' => "TEMPORARY",
+        other => return other.to_string(),
+    };
+    privilege.to_string()
+}
+
+/// (schema, table) pair identifying the table a catalog row belongs to
+type TableKey = (String, String);
+
+fn table_key(row: &PgRow) -> TableKey {
+    (row.get("table_schema"), row.get("table_name"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(grantee: &str, privilege: &str, grantable: bool) -> Grant {
+        Grant {
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable,
+        }
+    }
+
+    fn acl(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn split_acl_role_plain_and_public() {
+        assert_eq!(split_acl_role("app=r/owner"), ("app".to_string(), "=r/owner"));
+        assert_eq!(split_acl_role("=r/owner"), (String::new(), "=r/owner"));
+    }
+
+    #[test]
+    fn split_acl_role_quoted() {
+        assert_eq!(split_acl_role(r#""Auditors"=r/owner"#), ("Auditors".to_string(), "=r/owner"));
+        assert_eq!(split_acl_role(r#""a=b"=r/owner"#), ("a=b".to_string(), "=r/owner"));
+        assert_eq!(split_acl_role(r#""say ""hi"""=r/owner"#), (r#"say "hi""#.to_string(), "=r/owner"));
+    }
+
+    #[test]
+    fn parse_acl_public_entry() {
+        assert_eq!(parse_acl(&acl(&["=r/owner"]), "owner"), vec![grant("public", "SELECT", false)]);
+    }
+
+    #[test]
+    fn parse_acl_leaves_out_owner() {
+        let grants = parse_acl(&acl(&["owner=arwdDxt/owner", "app=rw/owner"]), "owner");
+        assert_eq!(grants, vec![grant("app", "SELECT", false), grant("app", "UPDATE", false)]);
+    }
+
+    #[test]
+    fn parse_acl_grant_options() {
+        let grants = parse_acl(&acl(&["app=r*w/owner"]), "owner");
+        assert_eq!(grants, vec![grant("app", "SELECT", true), grant("app", "UPDATE", false)]);
+    }
+
+    #[test]
+    fn parse_acl_quoted_roles() {
+        let grants = parse_acl(&acl(&[r#""Owner"=r/"Owner""#, r#""Auditors"=U*/"Owner""#]), "Owner");
+        assert_eq!(grants, vec![grant("Auditors", "USAGE", true)]);
+    }
 }
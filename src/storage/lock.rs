@@ -0,0 +1,350 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::schema::models::{
+    format, CompositeType, Domain, EnumType, EventTrigger, Grant, MaterializedView, Routine,
+    Schema, SchemaPrivileges, Sequence, Table, View,
+};
+
+/// Version of the lock file layout
+const LOCK_VERSION: u32 = 1;
+
+/// Committed, canonical representation of a schema.
+///
+/// Everything is sorted deterministically and the capture timestamp is
+/// left out, so the file only changes when the schema does and diffs
+/// cleanly in code review.
+#[derive(Debug, Serialize, Deserialize)]
+struct LockFile {
+    lock_version: u32,
+    /// Snapshot format the schema was captured with
+    #[serde(default)]
+    format: u32,
+    schemas: Vec<String>,
+    tables: Vec<Table>,
+    #[serde(default)]
+    sequences: Vec<Sequence>,
+    #[serde(default)]
+    enums: Vec<EnumType>,
+    #[serde(default)]
+    domains: Vec<Domain>,
+    #[serde(default)]
+    composite_types: Vec<CompositeType>,
+    #[serde(default)]
+    views: Vec<View>,
+    #[serde(default)]
+    materialized_views: Vec<MaterializedView>,
+    #[serde(default)]
+    routines: Vec<Routine>,
+    #[serde(default)]
+    event_triggers: Vec<EventTrigger>,
+    #[serde(default)]
+    schema_privileges: Vec<SchemaPrivileges>,
+}
+
+/// Write `schema` to a lock file at `path`
+pub fn write_lock(path: &Path, schema: &Schema) -> Result<()> {
+    let schema = canonicalize(schema);
+    let lock = LockFile {
+        lock_version: LOCK_VERSION,
+        format: schema.format,
+        schemas: schema.schema_names(),
+        tables: schema.tables,
+        sequences: schema.sequences,
+        enums: schema.enums,
+        domains: schema.domains,
+        composite_types: schema.composite_types,
+        views: schema.views,
+        materialized_views: schema.materialized_views,
+        routines: schema.routines,
+        event_triggers: schema.event_triggers,
+        schema_privileges: schema.schema_privileges,
+    };
+
+    let mut content = serde_json::to_string_pretty(&lock)
+        .context("Failed to serialize lock file")?;
+    content.push('\n');
+
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write lock file {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Read a schema from the lock file at `path`
+pub fn read_lock(path: &Path) -> Result<Schema> {
+    if !path.exists() {
+        anyhow::bail!(
+            "Lock file {} not found. Run 'dbdiff snapshot --write-lock' first.",
+            path.display()
+        );
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read lock file {}", path.display()))?;
+
+    let lock: LockFile = serde_json::from_str::<serde_json::Value>(&content)
+        .and_then(|value| {
+            let format = format::of(&value);
+            serde_json::from_value(value).map(|lock: LockFile| LockFile { format, ..lock })
+        })
+        .with_context(|| format!("Failed to parse lock file {}", path.display()))?;
+
+    if lock.lock_version > LOCK_VERSION {
+        anyhow::bail!(
+            "Lock file {} has version {}, but this dbdiff only supports up to {}. Upgrade dbdiff.",
+            path.display(),
+            lock.lock_version,
+            LOCK_VERSION
+        );
+    }
+
+    let mut schema = Schema {
+        schemas: lock.schemas,
+        sequences: lock.sequences,
+        enums: lock.enums,
+        domains: lock.domains,
+        composite_types: lock.composite_types,
+        views: lock.views,
+        materialized_views: lock.materialized_views,
+        routines: lock.routines,
+        event_triggers: lock.event_triggers,
+        schema_privileges: lock.schema_privileges,
+        format: lock.format,
+        ..Schema::with_tables(lock.tables)
+    };
+    schema.upgrade();
+
+    Ok(schema)
+}
+
+/// Sort every collection in the schema into a stable order
+fn canonicalize(schema: &Schema) -> Schema {
+    let mut schema = schema.clone();
+
+    schema.schemas.sort();
+    schema
+        .tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
+    for table in &mut schema.tables {
+        table.columns.sort_by_key(|c| c.ordinal_position);
+        table.indexes.sort_by(|a, b| a.name.cmp(&b.name));
+        table.constraints.sort_by(|a, b| a.name.cmp(&b.name));
+        table.triggers.sort_by(|a, b| a.name.cmp(&b.name));
+        table.policies.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_grants(&mut table.grants);
+        for column in &mut table.columns {
+            sort_grants(&mut column.grants);
+        }
+    }
+
+    schema
+        .sequences
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for sequence in &mut schema.sequences {
+        sort_grants(&mut sequence.grants);
+    }
+
+    // Enum labels and composite attributes keep their declared order
+    schema
+        .enums
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    schema
+        .domains
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for domain in &mut schema.domains {
+        domain.constraints.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    schema
+        .composite_types
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
+    schema
+        .views
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for view in &mut schema.views {
+        view.columns.sort_by_key(|c| c.ordinal_position);
+        sort_grants(&mut view.grants);
+        for column in &mut view.columns {
+            sort_grants(&mut column.grants);
+        }
+    }
+
+    schema
+        .materialized_views
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for view in &mut schema.materialized_views {
+        view.columns.sort_by_key(|c| c.ordinal_position);
+        view.indexes.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_grants(&mut view.grants);
+        for column in &mut view.columns {
+            sort_grants(&mut column.grants);
+        }
+    }
+
+    schema.routines.sort_by_key(Routine::signature);
+    for routine in &mut schema.routines {
+        sort_grants(&mut routine.grants);
+    }
+    schema.event_triggers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    schema.schema_privileges.sort_by(|a, b| a.name.cmp(&b.name));
+    for privileges in &mut schema.schema_privileges {
+        sort_grants(&mut privileges.grants);
+    }
+
+    schema
+}
+
+fn sort_grants(grants: &mut [Grant]) {
+    grants.sort_by(|a, b| (&a.grantee, &a.privilege).cmp(&(&b.grantee, &b.privilege)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::engine::SchemaDiff;
+    use crate::schema::models::{Column, Index};
+    use std::path::PathBuf;
+
+    fn grant(grantee: &str, privilege: &str) -> Grant {
+        Grant {
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable: false,
+        }
+    }
+
+    fn column(name: &str, position: i32, grants: Vec<Grant>) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: "integer".to_string(),
+            is_nullable: true,
+            default_value: None,
+            ordinal_position: position,
+            identity: None,
+            collation: None,
+            collation_schema: None,
+            generated: None,
+            grants,
+        }
+    }
+
+    fn index(name: &str, column: &str) -> Index {
+        Index {
+            name: name.to_string(),
+            columns: vec![column.to_string()],
+            is_unique: false,
+            is_primary: false,
+            method: Some("btree".to_string()),
+            key_options: Vec::new(),
+            include: Vec::new(),
+            predicate: None,
+            is_valid: true,
+            backs_constraint: false,
+        }
+    }
+
+    fn table(schema: &str, name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            schema: schema.to_string(),
+            columns: vec![
+                column("id", 1, Vec::new()),
+                column("total", 2, vec![grant("reporting", "SELECT"), grant("app", "UPDATE")]),
+            ],
+            indexes: vec![index(&format!("{}_id_idx", name), "id"), index(&format!("{}_total_idx", name), "total")],
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            partition_of: None,
+            rls_enabled: false,
+            rls_forced: false,
+            policies: Vec::new(),
+            owner: Some("app".to_string()),
+            grants: vec![grant("reporting", "SELECT"), grant("app", "INSERT"), grant("app", "DELETE")],
+        }
+    }
+
+    fn schema() -> Schema {
+        let mut schema = Schema::with_tables(vec![table("public", "orders"), table("audit", "log"), table("public", "customers")]);
+        schema.schemas = vec!["public".to_string(), "audit".to_string()];
+        schema.enums = vec![
+            EnumType {
+                name: "status".to_string(),
+                schema: "public".to_string(),
+                labels: vec!["open".to_string(), "closed".to_string()],
+            },
+            EnumType {
+                name: "level".to_string(),
+                schema: "audit".to_string(),
+                labels: vec!["info".to_string(), "error".to_string()],
+            },
+        ];
+        schema
+    }
+
+    /// Same schema with every collection in reverse order
+    fn shuffled(schema: &Schema) -> Schema {
+        let mut schema = schema.clone();
+        schema.schemas.reverse();
+        schema.tables.reverse();
+        schema.enums.reverse();
+        for table in &mut schema.tables {
+            table.columns.reverse();
+            table.indexes.reverse();
+            table.grants.reverse();
+            for column in &mut table.columns {
+                column.grants.reverse();
+            }
+        }
+        schema
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dbdiff-{}-{}.lock", name, std::process::id()))
+    }
+
+    #[test]
+    fn round_trips() {
+        let path = temp_path("round-trip");
+        let schema = canonicalize(&schema());
+
+        write_lock(&path, &schema).unwrap();
+        let read = read_lock(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read.format, format::CURRENT);
+        assert_eq!(read.schemas, schema.schemas);
+        assert_eq!(read.tables, schema.tables);
+        assert_eq!(read.enums, schema.enums);
+        assert!(!SchemaDiff::compare(&schema, &read).has_changes());
+    }
+
+    #[test]
+    fn writes_identical_bytes_for_any_input_order() {
+        let (first, second) = (temp_path("ordered"), temp_path("shuffled"));
+        let schema = schema();
+
+        write_lock(&first, &schema).unwrap();
+        write_lock(&second, &shuffled(&schema)).unwrap();
+        let (first_bytes, second_bytes) = (std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+        std::fs::remove_file(&first).unwrap();
+        std::fs::remove_file(&second).unwrap();
+
+        assert_eq!(first_bytes, second_bytes);
+    }
+
+    #[test]
+    fn rejects_newer_lock_versions() {
+        let path = temp_path("newer");
+        std::fs::write(&path, r#"{"lock_version": 2, "format": 16, "schemas": [], "tables": []}"#).unwrap();
+
+        let error = read_lock(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.to_string().contains("has version 2"), "{}", error);
+    }
+}
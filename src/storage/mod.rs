@@ -1 +1,2 @@
+pub mod lock;
 pub mod sqlite;
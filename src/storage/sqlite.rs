@@ -1,41 +1,197 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+
+use crate::config::Config;
 use crate::schema::models::Schema;
 
+/// Schema migrations for the local snapshot store, applied in order.
+/// The index of each entry + 1 is its version, tracked via `PRAGMA user_version`.
+const MIGRATIONS: &[&str] = &[
+    r#"
+    CREATE TABLE snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        table_count INTEGER NOT NULL,
+        schema_json TEXT NOT NULL
+    );
+    CREATE INDEX idx_snapshots_name ON snapshots (name);
+    "#,
+];
+
 /// Storage manager for local SQLite database
 pub struct Storage {
-    // TODO: SQLite connection pool
+    pool: SqlitePool,
 }
 
 impl Storage {
-    pub async fn new(_path: &str) -> Result<Self> {
-        // TODO: Initialize SQLite connection
-        // TODO: Run migrations
-        Ok(Self {})
+    pub async fn new(path: &str) -> Result<Self> {
+        // A plain filename, since `?` and `%` in a directory name would be
+        // taken for URL syntax
+        let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+
+        Self::connect(options)
+            .await
+            .with_context(|| format!("Failed to open snapshot database at {}", path))
+    }
+
+    /// An empty snapshot store that lives in memory, for tests
+    #[cfg(test)]
+    pub async fn in_memory() -> Result<Self> {
+        Self::connect(SqliteConnectOptions::new().in_memory(true)).await
+    }
+
+    async fn connect(options: SqliteConnectOptions) -> Result<Self> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        let storage = Self { pool };
+        storage.migrate().await?;
+
+        Ok(storage)
     }
 
-    pub async fn save_snapshot(&self, _name: &str, _schema: &Schema) -> Result<i64> {
-        // TODO: Serialize schema to JSON
-        // TODO: Insert into snapshots table
-        Ok(0)
+    /// Open the snapshot database inside the .dbdiff directory
+    pub async fn open() -> Result<Self> {
+        let path = Config::db_path();
+        Self::new(&path.to_string_lossy()).await
     }
 
-    pub async fn load_snapshot(&self, _id: i64) -> Result<Schema> {
-        // TODO: Query snapshot by ID
-        // TODO: Deserialize JSON to Schema
-        Ok(Schema::new())
+    /// Apply any migrations newer than the database's current version
+    async fn migrate(&self) -> Result<()> {
+        let current: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&self.pool)
+            .await?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            let version = index as i64 + 1;
+            if version <= current {
+                continue;
+            }
+
+            let mut tx = self.pool.begin().await?;
+            sqlx::raw_sql(migration)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to apply storage migration {}", version))?;
+            sqlx::raw_sql(&format!("PRAGMA user_version = {}", version))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn save_snapshot(&self, name: &str, schema: &Schema) -> Result<i64> {
+        let schema_json = serde_json::to_string(schema)
+            .context("Failed to serialize schema")?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO snapshots (name, created_at, table_count, schema_json)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(name)
+        .bind(schema.captured_at)
+        .bind(schema.table_count() as i64)
+        .bind(schema_json)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save snapshot")?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn load_snapshot(&self, id: i64) -> Result<Schema> {
+        let row = sqlx::query("SELECT schema_json FROM snapshots WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => parse_schema(row.get("schema_json")),
+            None => anyhow::bail!("Snapshot {} not found", id),
+        }
     }
 
     pub async fn get_latest_snapshot(&self) -> Result<Option<Schema>> {
-        // TODO: Query most recent snapshot
-        Ok(None)
+        let row = sqlx::query("SELECT schema_json FROM snapshots ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| parse_schema(row.get("schema_json")))
+            .transpose()
     }
 
-    pub async fn list_snapshots(&self, _limit: u32) -> Result<Vec<SnapshotInfo>> {
-        // TODO: Query snapshots with metadata
-        Ok(Vec::new())
+    /// Check whether a snapshot with the given ID exists
+    pub async fn snapshot_exists(&self, id: i64) -> Result<bool> {
+        let found: Option<i64> = sqlx::query_scalar("SELECT id FROM snapshots WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(found.is_some())
+    }
+
+    /// Find the most recent snapshot with the given name
+    pub async fn find_snapshot_by_name(&self, name: &str) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar("SELECT id FROM snapshots WHERE name = $1 ORDER BY id DESC LIMIT 1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Find the ID of the snapshot `offset` steps before the latest (0 = latest)
+    pub async fn get_snapshot_id_from_latest(&self, offset: u32) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar("SELECT id FROM snapshots ORDER BY id DESC LIMIT 1 OFFSET $1")
+            .bind(offset as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    pub async fn list_snapshots(&self, limit: u32) -> Result<Vec<SnapshotInfo>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, created_at, table_count
+            FROM snapshots
+            ORDER BY id DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let snapshots = rows
+            .iter()
+            .map(|row| {
+                let table_count: i64 = row.get("table_count");
+                SnapshotInfo {
+                    id: row.get("id"),
+                    name: row.get("name"),
+                    created_at: row.get("created_at"),
+                    table_count: table_count as usize,
+                }
+            })
+            .collect();
+
+        Ok(snapshots)
     }
 }
 
+fn parse_schema(json: &str) -> Result<Schema> {
+    Schema::from_json(json).context("Failed to parse stored snapshot")
+}
+
 #[derive(Debug)]
 pub struct SnapshotInfo {
     pub id: i64,
@@ -43,3 +199,42 @@ pub struct SnapshotInfo {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub table_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn migrations_set_user_version() {
+        let storage = Storage::in_memory().await.unwrap();
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(version, MIGRATIONS.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn migrations_run_once() {
+        let storage = Storage::in_memory().await.unwrap();
+        storage.migrate().await.unwrap();
+        storage.save_snapshot("first", &Schema::new()).await.unwrap();
+
+        assert_eq!(storage.list_snapshots(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn opens_paths_with_url_characters() {
+        let dir = std::env::temp_dir().join(format!("dbdiff test?a=1%20 {}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshots.db");
+
+        let storage = Storage::new(&path.to_string_lossy()).await.unwrap();
+        storage.save_snapshot("first", &Schema::new()).await.unwrap();
+        drop(storage);
+
+        assert!(path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}